@@ -0,0 +1,64 @@
+use std::{fs, hint::black_box, path::Path};
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use thermal::commands::{
+    Command,
+    reader::state::{Mode, ParserState},
+};
+use winnow::Partial;
+
+struct Standard;
+
+impl ParserState for Standard {
+    fn mode(&self) -> Mode {
+        Mode::Standard
+    }
+}
+
+fn examples() -> Vec<(String, Vec<u8>)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+
+    let mut files = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dat"))
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            (name, fs::read(path).unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    files.sort();
+    files
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+
+    for (name, bytes) in examples() {
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("parse", &name), &bytes, |b, bytes| {
+            b.iter(|| {
+                let mut input = Partial::new(&bytes[..]);
+                while !input.is_empty() {
+                    black_box(Command::parse(&mut input, &Standard).unwrap());
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("parse_ref", &name), &bytes, |b, bytes| {
+            b.iter(|| {
+                let mut input = Partial::new(&bytes[..]);
+                while !input.is_empty() {
+                    black_box(Command::parse_ref(&mut input, &Standard).unwrap());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+use std::fmt;
+
+use crate::commands::CommandCategory;
+
+/// What the reference says about a command, for help texts and diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandInfo {
+    /// Key of the command's reference page, e.g. `dle_eot`.
+    pub id: &'static str,
+    pub name: &'static str,
+    /// The "[Description]" section, empty if the page has none.
+    pub description: &'static str,
+    pub category: CommandCategory,
+    /// The "[Notes]" section, one entry per note. Tables are in markdown.
+    pub notes: &'static [&'static str],
+    pub obsolete: bool,
+    /// Whether the printer processes the command as soon as it arrives, ahead of the print
+    /// buffer.
+    pub is_realtime: bool,
+    /// The command's reference page.
+    pub url: Option<&'static str>,
+}
+
+impl fmt::Display for CommandInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.obsolete {
+            write!(f, " (obsolete)")?;
+        }
+
+        if !self.description.is_empty() {
+            write!(f, "\n\n{}", self.description)?;
+        }
+
+        // Tables span several lines and read better set apart from the list
+        let mut after_table = false;
+        for note in self.notes {
+            let is_table = note.contains('\n');
+            if is_table || after_table {
+                writeln!(f)?;
+            }
+            if is_table {
+                write!(f, "\n{note}")?;
+            } else {
+                write!(f, "\n- {note}")?;
+            }
+            after_table = is_table;
+        }
+
+        if let Some(url) = self.url {
+            write!(f, "\n\n{url}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,15 +1,24 @@
+pub mod info;
 pub mod reader;
+pub mod realtime;
+pub mod validation;
 pub mod writer;
 
 use thermal_escpos_macros::escpos_commands;
 
 escpos_commands! {
-    SPEC_PATH = "../../vendor/spec/escpos-commands.json",
     enum_name = Command,
 
-    derive = "std::clone::Clone, facet::Facet, strum::Display, strum::EnumCount, strum::EnumDiscriminants, strum::EnumMessage",
+    derive = "std::clone::Clone, std::fmt::Debug, std::cmp::PartialEq, facet::Facet, strum::Display, strum::EnumCount, strum::EnumDiscriminants, strum::EnumMessage",
     strum_discriminants_derive = "strum::EnumCount, strum::EnumIter, strum::EnumMessage",
 
     category_enum_name = CommandCategory,
-    category_derive = "strum::EnumCount, strum::EnumIter, strum::EnumMessage",
+    category_derive = "std::clone::Clone, std::marker::Copy, std::fmt::Debug, std::cmp::PartialEq, std::cmp::Eq, strum::EnumCount, strum::EnumIter, strum::EnumMessage",
+
+    model_enum_name = Model,
+    realtime_enum_name = RealtimeCommand,
+
+    parser_error = "winnow::error::ErrMode<winnow::error::ContextError<crate::commands::reader::error::ErrorCtx>>",
+    range_error = "crate::commands::validation::RangeError",
+    command_info = "crate::commands::info::CommandInfo",
 }
@@ -0,0 +1,190 @@
+use winnow::{
+    Parser, Partial,
+    binary::le_u16,
+    combinator::eof,
+    error::{ContextError, ErrMode},
+    stream::StreamIsPartial,
+    token::{literal, rest, take},
+};
+
+use crate::{
+    commands::{
+        Command, GraphicsFunction, SymbolFunction,
+        reader::{
+            Output,
+            commands::{command, text},
+            error::ErrorCtx,
+            state::ParserState,
+        },
+    },
+    types::{
+        bit_image_band::BitImageBandRef,
+        raster::{GraphicsDataRef, RasterImageRef},
+        symbol::SymbolData,
+        user_defined_characters::UserDefinedCharactersRef,
+    },
+};
+
+/// A decoded command that borrows its large payloads from the input.
+#[derive(Clone)]
+pub enum CommandRef<'i> {
+    SelectBitImageMode(BitImageBandRef<'i>),
+    DefineUserDefinedCharacters(UserDefinedCharactersRef<'i>),
+    PrintRasterBitImage(RasterImageRef<'i>),
+    /// `GS ( L` fn 112.
+    StoreTheGraphicsDataInThePrintBuffer(GraphicsDataRef<'i>),
+    /// `GS ( k` fn 180, the data `d1…dk` only.
+    QrStoreData(&'i [u8]),
+    /// `GS ( k` fn 080, the data `d1…dk` only.
+    Pdf417StoreData(&'i [u8]),
+    /// `GS ( k` fn 680, the data `d1…dk` only.
+    DataMatrixStoreData(&'i [u8]),
+    /// Commands without large payloads are decoded eagerly.
+    Owned(Command),
+}
+
+impl<'i> CommandRef<'i> {
+    pub fn into_owned(self) -> Command {
+        match self {
+            Self::SelectBitImageMode(band) => Command::SelectBitImageMode(band.into_owned()),
+            Self::DefineUserDefinedCharacters(seq) => {
+                Command::DefineUserDefinedCharacters(seq.iter().map(|c| c.into_owned()).collect())
+            }
+            Self::PrintRasterBitImage(image) => Command::PrintRasterBitImage(image.into_owned()),
+            Self::StoreTheGraphicsDataInThePrintBuffer(graphics) => Command::Graphics(
+                GraphicsFunction::StoreTheGraphicsDataInThePrintBuffer(graphics.into_owned()),
+            ),
+            Self::QrStoreData(data) => {
+                Command::Symbol(SymbolFunction::QrStoreData(SymbolData::new(data.to_vec())))
+            }
+            Self::Pdf417StoreData(data) => Command::Symbol(SymbolFunction::Pdf417StoreData(
+                SymbolData::new(data.to_vec()),
+            )),
+            Self::DataMatrixStoreData(data) => Command::Symbol(
+                SymbolFunction::DataMatrixStoreData(SymbolData::new(data.to_vec())),
+            ),
+            Self::Owned(command) => command,
+        }
+    }
+}
+
+/// Borrowed counterpart of [`Output`].
+#[derive(Clone)]
+pub enum OutputRef<'i> {
+    Command(CommandRef<'i>),
+    /// A run of printable bytes between two commands.
+    Text(&'i [u8]),
+}
+
+impl<'i> OutputRef<'i> {
+    pub fn into_owned(self) -> Vec<Output> {
+        match self {
+            Self::Command(command) => vec![Output::Command(command.into_owned())],
+            Self::Text(text) => vec![Output::Text(text.to_vec())],
+        }
+    }
+}
+
+/// `GS ( L` fn 112.
+const STORE_GRAPHICS: (&[u8], &[u8]) = (&[0x1D, 0x28, 0x4C], &[48, 112]);
+/// `GS ( k` fn 180, 080 and 680, each with the `m` byte that follows.
+const QR_STORE: (&[u8], &[u8]) = (&[0x1D, 0x28, 0x6B], &[49, 80, 48]);
+const PDF417_STORE: (&[u8], &[u8]) = (&[0x1D, 0x28, 0x6B], &[48, 80, 48]);
+const DATA_MATRIX_STORE: (&[u8], &[u8]) = (&[0x1D, 0x28, 0x6B], &[54, 80, 48]);
+
+/// Whether `input` starts with the `pL pH`-framed function `(prefix, function)`.
+fn starts_with_function(input: &[u8], (prefix, function): (&[u8], &[u8])) -> bool {
+    input.starts_with(prefix)
+        && input
+            .get(prefix.len() + 2..)
+            .is_some_and(|block| block.starts_with(function))
+}
+
+/// The block of a `pL pH`-framed function past its function bytes, as complete input.
+fn function_block<'i>(
+    (prefix, function): (&'static [u8], &'static [u8]),
+) -> impl Parser<Partial<&'i [u8]>, Partial<&'i [u8]>, ErrMode<ContextError<ErrorCtx>>> {
+    move |input: &mut Partial<&'i [u8]>| {
+        take(prefix.len()).parse_next(input)?;
+        let len = le_u16.parse_next(input)?;
+        let block = take(len).parse_next(input)?;
+
+        let mut block = Partial::new(block);
+        let _ = block.complete();
+        literal(function).parse_next(&mut block)?;
+
+        Ok(block)
+    }
+}
+
+pub fn command_ref<'i>(
+    state: &impl ParserState,
+) -> impl Parser<Partial<&'i [u8]>, OutputRef<'i>, ErrMode<ContextError<ErrorCtx>>> {
+    move |input: &mut Partial<&'i [u8]>| {
+        let text = text.parse_next(input)?;
+        if !text.is_empty() {
+            return Ok(OutputRef::Text(text));
+        }
+
+        if input.starts_with(&[0x1B, 0x2A]) {
+            return (take(2usize), BitImageBandRef::parser())
+                .map(|(_, band)| OutputRef::Command(CommandRef::SelectBitImageMode(band)))
+                .parse_next(input);
+        }
+
+        if input.starts_with(&[0x1B, 0x26]) {
+            return (take(2usize), UserDefinedCharactersRef::parser())
+                .map(|(_, seq)| OutputRef::Command(CommandRef::DefineUserDefinedCharacters(seq)))
+                .parse_next(input);
+        }
+
+        if input.starts_with(&[0x1D, 0x76, 0x30]) {
+            return (take(3usize), RasterImageRef::parser())
+                .map(|(_, image)| OutputRef::Command(CommandRef::PrintRasterBitImage(image)))
+                .parse_next(input);
+        }
+
+        if starts_with_function(input, STORE_GRAPHICS) {
+            let mut block = function_block(STORE_GRAPHICS).parse_next(input)?;
+            let graphics = GraphicsDataRef::parser().parse_next(&mut block)?;
+            eof.parse_next(&mut block)?;
+            return Ok(OutputRef::Command(
+                CommandRef::StoreTheGraphicsDataInThePrintBuffer(graphics),
+            ));
+        }
+
+        for (function, command) in [
+            (
+                QR_STORE,
+                CommandRef::QrStoreData as fn(&'i [u8]) -> CommandRef<'i>,
+            ),
+            (PDF417_STORE, CommandRef::Pdf417StoreData),
+            (DATA_MATRIX_STORE, CommandRef::DataMatrixStoreData),
+        ] {
+            if starts_with_function(input, function) {
+                let mut block = function_block(function).parse_next(input)?;
+                let data = rest.parse_next(&mut block)?;
+                return Ok(OutputRef::Command(command(data)));
+            }
+        }
+
+        let start = *input;
+
+        command(state).parse_next(input).map(|output| match output {
+            Output::Command(command) => OutputRef::Command(CommandRef::Owned(command)),
+            Output::Raw(_) | Output::Text(_) => {
+                OutputRef::Text(&start[..start.len() - input.len()])
+            }
+        })
+    }
+}
+
+impl Command {
+    /// Like [`Command::parse`], but keeps text runs and image payloads as slices of `input`.
+    pub fn parse_ref<'i>(
+        input: &mut Partial<&'i [u8]>,
+        state: &impl ParserState,
+    ) -> winnow::Result<OutputRef<'i>, ErrMode<ContextError<ErrorCtx>>> {
+        command_ref(state).parse_next(input)
+    }
+}
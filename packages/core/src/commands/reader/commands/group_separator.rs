@@ -14,12 +14,7 @@ use crate::{
             state::{Mode, ParserState},
         },
     },
-    types::{
-        cut_mode::{CutMode, CuttingShape},
-        pulse_info::RealtimePulseInfo,
-        realtime_request::RealtimeRequest,
-        requested_status::RequestedStatus,
-    },
+    types::realtime_commands::RealtimeCommandSetting,
 };
 
 pub fn gs_command<'i>(
@@ -32,18 +27,8 @@ pub fn gs_command<'i>(
             Command::SelectCharacterSize(width, height)
         }),
 
-        0x56 => dispatch!(take(1usize).map(|v: &[u8]| v[0]);
-            0x00 | b'0' => empty.value(Command::SelectCutModeAndCutPaper(CutMode::Cut(CuttingShape::Full))),
-            0x01 | b'1' => empty.value(Command::SelectCutModeAndCutPaper(CutMode::Cut(CuttingShape::Partial))),
-
-            b'A' => u8.map(|v| Command::SelectCutModeAndCutPaper(CutMode::FeedAndCut(v, CuttingShape::Full))),
-            b'B' => u8.map(|v| Command::SelectCutModeAndCutPaper(CutMode::FeedAndCut(v, CuttingShape::Partial))),
-
-            b'a' => u8.map(|v| Command::SelectCutModeAndCutPaper(CutMode::SetCuttingPosition(v, CuttingShape::Full))),
-            b'b' => u8.map(|v| Command::SelectCutModeAndCutPaper(CutMode::SetCuttingPosition(v, CuttingShape::Partial))),
-
-            b'g' => u8.map(|v| Command::SelectCutModeAndCutPaper(CutMode::FeedAndCutAndMoveToStart(v, CuttingShape::Full))),
-            b'h' => u8.map(|v| Command::SelectCutModeAndCutPaper(CutMode::FeedAndCutAndMoveToStart(v, CuttingShape::Partial))),
+        0x28 => dispatch!(take(1usize).map(|v: &[u8]| v[0]);
+            0x44 => RealtimeCommandSetting::sequence_parser().map(|settings| Command::EnableDisableRealTimeCommand(settings)),
 
             _ => fail,
         ),
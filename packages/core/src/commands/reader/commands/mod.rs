@@ -5,8 +5,9 @@ pub mod group_separator;
 
 use winnow::{
     Parser, Partial,
-    combinator::{dispatch, empty, fail},
-    error::ContextError,
+    combinator::{alt, dispatch, empty, fail},
+    error::{ContextError, ErrMode},
+    stream::Stream,
     token::take,
 };
 
@@ -23,14 +24,87 @@ use crate::commands::{
     },
 };
 
+/// Bytes that start a command rather than being printed as text.
+pub fn is_control(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'\t' | b'\n' | b'\r' | 0x0C | 0x10 | 0x18 | 0x1B | 0x1C | 0x1D
+    )
+}
+
+/// Position of the first control byte in `haystack`.
+///
+/// Every control byte is below 0x20, so this scans a word at a time for bytes below 0x20 and
+/// only checks individual bytes in words that contain one.
+pub fn find_control(haystack: &[u8]) -> Option<usize> {
+    const LO: u64 = 0x0101_0101_0101_0101;
+    const HI: u64 = 0x8080_8080_8080_8080;
+
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+
+        if (word.wrapping_sub(LO * 0x20) & !word & HI) != 0
+            && let Some(i) = chunk.iter().position(|b| is_control(*b))
+        {
+            return Some(offset + i);
+        }
+
+        offset += 8;
+    }
+
+    chunks
+        .remainder()
+        .iter()
+        .position(|b| is_control(*b))
+        .map(|i| offset + i)
+}
+
+/// Consumes printable bytes up to the next control byte (or the end of the available input).
+pub fn text<'i>(
+    input: &mut Partial<&'i [u8]>,
+) -> winnow::Result<&'i [u8], ErrMode<ContextError<ErrorCtx>>> {
+    let len = find_control(input).unwrap_or(input.len());
+
+    take(len).parse_next(input)
+}
+
 pub fn command<'i>(
     state: &impl ParserState,
 ) -> impl Parser<Partial<&'i [u8]>, Output, winnow::error::ErrMode<ContextError<ErrorCtx>>> {
-    dispatch!(take(1usize).map(|v: &[u8]| v[0]);
-        b'\t' => empty.value(Output::Command(Command::HorizontalTab)),
-        b'\n' => empty.value(Output::Command(Command::LineFeed)),
-        b'\r' => empty.value(Output::Command(Command::CarriageReturn)),
+    let validate = state.validate();
+    let mut parser = alt((
+        text.verify(|t: &[u8]| !t.is_empty())
+            .map(|t: &[u8]| Output::Text(t.to_vec())),
+        control_command(state),
+        Command::parse_declared.map(Output::Command),
+    ));
+
+    move |input: &mut Partial<&'i [u8]>| {
+        let start = input.checkpoint();
+        let output = parser.parse_next(input)?;
+
+        if validate
+            && let Output::Command(command) = &output
+            && let Err(e) = command.validate()
+        {
+            input.reset(&start);
+
+            let mut error = ContextError::new();
+            error.push(ErrorCtx::OutOfRange(e));
+            return Err(ErrMode::Cut(error));
+        }
 
+        Ok(output)
+    }
+}
+
+fn control_command<'i>(
+    state: &impl ParserState,
+) -> impl Parser<Partial<&'i [u8]>, Output, winnow::error::ErrMode<ContextError<ErrorCtx>>> {
+    dispatch!(take(1usize).map(|v: &[u8]| v[0]);
         0x1B => esc_command(state).map(|v| Output::Command(v)),
         0x18 => empty.value(Output::Command(Command::CancelPrintDataInPageMode)),
         0x10 => dle_command(state).map(|v| Output::Command(v)),
@@ -42,6 +116,6 @@ pub fn command<'i>(
         0x1C => fs_command(state).map(|v| Output::Command(v)),
         0x1D => gs_command(state).map(|v| Output::Command(v)),
 
-        v => empty.value(Output::Raw(v)),
+        _ => fail,
     )
 }
@@ -0,0 +1,354 @@
+use std::collections::VecDeque;
+
+use winnow::{
+    Partial,
+    error::{ContextError, ErrMode},
+};
+
+use crate::{
+    commands::{
+        Command, RealtimeCommand,
+        reader::{Output, error::ErrorCtx, state::ParserState},
+    },
+    types::realtime_commands::{RealtimeCommandKind, RealtimeCommands},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Output(Output),
+    /// A real-time command picked out of the byte stream, possibly from the middle of another
+    /// command's parameter data. It is reported before the command it was embedded in.
+    Realtime(RealtimeCommand),
+}
+
+enum Realtime {
+    Found {
+        len: usize,
+        command: RealtimeCommand,
+    },
+    Incomplete,
+    None,
+}
+
+/// Buffered decoder over a byte stream received from (or sent to) a printer.
+///
+/// By default commands are only recognised at command boundaries, exactly like [`Command::parse`].
+/// With [`Decoder::with_realtime_commands`] the decoder behaves like the firmware with real-time
+/// commands enabled: `DLE EOT`, `DLE ENQ` and `DLE DC4` sequences are picked out of the stream at
+/// any position, removed from the data, and reported as [`Event::Realtime`]. `GS ( D` and
+/// `ESC @` in the stream update which real-time commands are recognised.
+pub struct Decoder<S: ParserState> {
+    state: S,
+    realtime: Option<RealtimeCommands>,
+    buffer: Vec<u8>,
+    offset: usize,
+    pending: VecDeque<Event>,
+}
+
+impl<S: ParserState> Decoder<S> {
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            realtime: None,
+            buffer: Vec::new(),
+            offset: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn with_realtime_commands(mut self, enabled: RealtimeCommands) -> Self {
+        self.realtime = Some(enabled);
+        self
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// The real-time commands currently being recognised, if real-time scanning is on.
+    pub fn realtime_commands(&self) -> Option<RealtimeCommands> {
+        self.realtime
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        if self.offset > 0 {
+            self.buffer.drain(..self.offset);
+            self.offset = 0;
+        }
+
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Bytes received but not decoded yet.
+    pub fn remaining(&self) -> &[u8] {
+        &self.buffer[self.offset..]
+    }
+
+    fn realtime_at(&self, data: &[u8], enabled: &RealtimeCommands) -> Realtime {
+        if data.first() != Some(&0x10) {
+            return Realtime::None;
+        }
+
+        let mut input = Partial::new(data);
+
+        match Command::parse_declared(&mut input) {
+            Ok(command) => {
+                let Ok(command) = RealtimeCommand::try_from(command) else {
+                    return Realtime::None;
+                };
+
+                let kind = match command {
+                    RealtimeCommand::RealtimeGeneratePulse(_) => {
+                        Some(RealtimeCommandKind::GeneratePulse)
+                    }
+                    RealtimeCommand::ExecutePowerOffSequence => {
+                        Some(RealtimeCommandKind::PowerOffSequence)
+                    }
+                    _ => None,
+                };
+
+                if kind.is_some_and(|kind| !enabled.is_enabled(kind)) {
+                    return Realtime::None;
+                }
+
+                Realtime::Found {
+                    len: data.len() - input.len(),
+                    command,
+                }
+            }
+            Err(ErrMode::Incomplete(_)) => Realtime::Incomplete,
+            Err(_) => Realtime::None,
+        }
+    }
+
+    fn decode_plain(
+        &mut self,
+        end: usize,
+    ) -> Option<Result<Output, ErrMode<ContextError<ErrorCtx>>>> {
+        let data = &self.buffer[self.offset..self.offset + end];
+        let mut input = Partial::new(data);
+
+        match Command::parse(&mut input, &self.state) {
+            Ok(output) => {
+                self.offset += data.len() - input.len();
+                Some(Ok(output))
+            }
+            Err(ErrMode::Incomplete(_)) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Decode a command whose data has real-time sequences embedded in it.
+    fn decode_filtered(
+        &mut self,
+        enabled: &RealtimeCommands,
+    ) -> Option<Result<Output, ErrMode<ContextError<ErrorCtx>>>> {
+        let data = &self.buffer[self.offset..];
+
+        let mut filtered = Vec::with_capacity(data.len());
+        let mut origin = Vec::with_capacity(data.len());
+        let mut embedded = Vec::new();
+
+        let mut i = 0;
+        while i < data.len() {
+            match self.realtime_at(&data[i..], enabled) {
+                Realtime::Found { len, command } => {
+                    embedded.push((i, command));
+                    i += len;
+                }
+                // We can't tell whether this is data or a real-time command yet.
+                Realtime::Incomplete => break,
+                Realtime::None => {
+                    filtered.push(data[i]);
+                    origin.push(i);
+                    i += 1;
+                }
+            }
+        }
+
+        let mut input = Partial::new(&filtered[..]);
+
+        match Command::parse(&mut input, &self.state) {
+            Ok(output) => {
+                let consumed = origin[filtered.len() - input.len() - 1] + 1;
+
+                for (_, command) in embedded.into_iter().filter(|(at, _)| *at < consumed) {
+                    self.pending.push_back(Event::Realtime(command));
+                }
+
+                self.offset += consumed;
+                Some(Ok(output))
+            }
+            Err(ErrMode::Incomplete(_)) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn track(&mut self, output: &Output) {
+        let Some(realtime) = &mut self.realtime else {
+            return;
+        };
+
+        match output {
+            Output::Command(Command::EnableDisableRealTimeCommand(settings)) => {
+                for setting in settings {
+                    realtime.apply(*setting);
+                }
+            }
+            Output::Command(Command::InitializePrinter) => *realtime = RealtimeCommands::default(),
+            _ => {}
+        }
+    }
+}
+
+impl<S: ParserState> Iterator for Decoder<S> {
+    type Item = Result<Event, ContextError<ErrorCtx>>;
+
+    /// Returns `None` once more input is needed to make progress.
+    ///
+    /// On a decode error, the offending byte is skipped so that decoding can resume.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(Ok(event));
+        }
+
+        let len = self.buffer.len() - self.offset;
+        if len == 0 {
+            return None;
+        }
+
+        let result = match self.realtime {
+            None => self.decode_plain(len),
+            Some(enabled) => {
+                let data = &self.buffer[self.offset..];
+                let mut boundary = len;
+
+                for at in (0..len).filter(|at| data[*at] == 0x10) {
+                    match self.realtime_at(&data[at..], &enabled) {
+                        Realtime::Found { len, command } if at == 0 => {
+                            self.offset += len;
+                            return Some(Ok(Event::Realtime(command)));
+                        }
+                        Realtime::Incomplete if at == 0 => return None,
+                        Realtime::None => continue,
+                        _ => {
+                            boundary = at;
+                            break;
+                        }
+                    }
+                }
+
+                match self.decode_plain(boundary) {
+                    None if boundary < len => self.decode_filtered(&enabled),
+                    result => result,
+                }
+            }
+        };
+
+        match result? {
+            Ok(output) => {
+                self.track(&output);
+
+                match self.pending.pop_front() {
+                    Some(event) => {
+                        self.pending.push_back(Event::Output(output));
+                        Some(Ok(event))
+                    }
+                    None => Some(Ok(Event::Output(output))),
+                }
+            }
+            Err(e) => {
+                self.offset += 1;
+
+                Some(Err(match e {
+                    ErrMode::Backtrack(e) | ErrMode::Cut(e) => e,
+                    ErrMode::Incomplete(_) => unreachable!(),
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{commands::reader::state::Mode, types::requested_status::RequestedStatus};
+
+    struct Standard;
+
+    impl ParserState for Standard {
+        fn mode(&self) -> Mode {
+            Mode::Standard
+        }
+    }
+
+    fn decode(decoder: &mut Decoder<Standard>) -> Vec<Event> {
+        decoder.by_ref().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn realtime_command_inside_parameters() {
+        let mut decoder = Decoder::new(Standard).with_realtime_commands(Default::default());
+        decoder.push(b"\x1bJ\x10\x04\x01\x05");
+
+        assert_eq!(
+            decode(&mut decoder),
+            [
+                Event::Realtime(RealtimeCommand::RequestStatus(RequestedStatus::Printer)),
+                Event::Output(Output::Command(Command::PrintAndFeedPaper(5))),
+            ]
+        );
+    }
+
+    #[test]
+    fn realtime_command_across_pushes() {
+        let mut decoder = Decoder::new(Standard).with_realtime_commands(Default::default());
+        decoder.push(b"\x10\x04\x07");
+        assert_eq!(decode(&mut decoder), []);
+
+        decoder.push(b"\x01");
+        assert_eq!(
+            decode(&mut decoder),
+            [Event::Realtime(RealtimeCommand::RequestStatus(
+                RequestedStatus::Ink { channel: 1 }
+            ))]
+        );
+    }
+
+    #[test]
+    fn disabled_realtime_command_is_a_command() {
+        let disabled = RealtimeCommands {
+            generate_pulse: false,
+            ..Default::default()
+        };
+        let mut decoder = Decoder::new(Standard).with_realtime_commands(disabled);
+        decoder.push(b"\x10\x14\x01\x00\x01");
+
+        assert!(matches!(
+            decode(&mut decoder)[..],
+            [Event::Output(Output::Command(
+                Command::RealtimeGeneratePulse(_)
+            ))]
+        ));
+    }
+
+    #[test]
+    fn without_realtime_commands_parameters_are_kept() {
+        let mut decoder = Decoder::new(Standard);
+        decoder.push(b"\x1bJ\x10\x10\x04\x01");
+
+        assert_eq!(
+            decode(&mut decoder),
+            [
+                Event::Output(Output::Command(Command::PrintAndFeedPaper(0x10))),
+                Event::Output(Output::Command(Command::RequestStatus(
+                    RequestedStatus::Printer
+                ))),
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use winnow::{Partial, error::ParserError};
+use crate::commands::validation::RangeError;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ErrorLabel {
@@ -20,5 +20,9 @@ pub enum ErrorExpected {
 pub enum ErrorCtx {
     Label(ErrorLabel),
     Expected(ErrorExpected),
+    /// The command was recognised but a parameter failed [`Command::validate`].
+    ///
+    /// [`Command::validate`]: crate::commands::Command::validate
+    OutOfRange(RangeError),
     Unimplemented,
 }
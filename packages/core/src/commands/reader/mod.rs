@@ -1,4 +1,6 @@
+pub mod borrowed;
 pub mod commands;
+pub mod decoder;
 pub mod error;
 pub mod state;
 
@@ -13,11 +15,13 @@ use crate::commands::{
     reader::{commands::command, error::ErrorCtx, state::ParserState},
 };
 
-#[derive(Clone, Facet)]
+#[derive(Clone, Debug, PartialEq, Facet)]
 #[repr(C)]
 pub enum Output {
     Command(Command),
     Raw(u8),
+    /// A run of printable bytes between two commands.
+    Text(Vec<u8>),
 }
 
 impl Command {
@@ -1,6 +1,6 @@
 use strum::EnumIs;
 
-#[derive(Default, EnumIs, Clone, Copy)]
+#[derive(Default, EnumIs, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Mode {
     #[default]
     Standard,
@@ -9,4 +9,10 @@ pub enum Mode {
 
 pub trait ParserState {
     fn mode(&self) -> Mode;
+
+    /// Reject commands whose parameters are outside the reference's ranges, instead of
+    /// returning them as parsed.
+    fn validate(&self) -> bool {
+        false
+    }
 }
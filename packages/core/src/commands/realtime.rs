@@ -0,0 +1,10 @@
+use std::io::Write;
+
+use crate::commands::{Command, RealtimeCommand, writer::error::EncodeError};
+
+impl RealtimeCommand {
+    /// Encode the command, returning the number of bytes written.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<usize, EncodeError> {
+        Command::from(self.clone()).write_to(w)
+    }
+}
@@ -0,0 +1,25 @@
+use std::fmt;
+
+use crate::commands::info::CommandInfo;
+
+/// A parameter outside the range its command's reference page allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeError {
+    /// The command, or the function of a function-coded command, the parameter belongs to.
+    pub command: &'static CommandInfo,
+    pub parameter: &'static str,
+    /// The allowed range as written in the reference, e.g. `1 ≤ n ≤ 8`.
+    pub range: &'static str,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} is out of range ({})",
+            self.command.name, self.parameter, self.range
+        )
+    }
+}
+
+impl std::error::Error for RangeError {}
@@ -0,0 +1,227 @@
+use std::io::Write;
+
+use crate::commands::{reader::Output, writer::error::EncodeError};
+
+impl Output {
+    /// Encode the output, returning the number of bytes written.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<usize, EncodeError> {
+        match self {
+            Self::Command(command) => command.write_to(w),
+            Self::Raw(byte) => {
+                w.write_all(&[*byte])?;
+                Ok(1)
+            }
+            Self::Text(text) => {
+                w.write_all(text)?;
+                Ok(text.len())
+            }
+        }
+    }
+}
+
+/// Serializes [`Output`]s into any [`Write`], keeping track of how much has been written.
+///
+/// Use a `Vec<u8>` as the writer to encode into memory.
+pub struct Encoder<W: Write> {
+    inner: W,
+    position: usize,
+    validate: bool,
+    /// Real-time commands held back from `inner`, if they take the priority path.
+    realtime: Option<Vec<u8>>,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            position: 0,
+            validate: false,
+            realtime: None,
+        }
+    }
+
+    /// Check every command with [`Command::validate`] before writing it, failing with
+    /// [`EncodeError::Invalid`] instead of sending parameters the printer doesn't accept.
+    ///
+    /// [`Command::validate`]: crate::commands::Command::validate
+    pub fn with_validation(mut self) -> Self {
+        self.validate = true;
+        self
+    }
+
+    /// Encode real-time commands into a queue of their own instead of the writer, so that they
+    /// can be sent ahead of the data before them. See [`Encoder::take_realtime`].
+    pub fn with_realtime_queue(mut self) -> Self {
+        self.realtime = Some(Vec::new());
+        self
+    }
+
+    /// Encode one output, returning the number of bytes it took.
+    pub fn encode(&mut self, output: &Output) -> Result<usize, EncodeError> {
+        if let Output::Command(command) = output {
+            if self.validate {
+                command.validate()?;
+            }
+
+            if let Some(queue) = &mut self.realtime
+                && command.is_realtime()
+            {
+                return command.write_to(queue);
+            }
+        }
+
+        let len = output.write_to(&mut self.inner)?;
+        self.position += len;
+        Ok(len)
+    }
+
+    /// Encode every output in order, returning the number of bytes each one took.
+    pub fn encode_all<'o>(
+        &mut self,
+        outputs: impl IntoIterator<Item = &'o Output>,
+    ) -> Result<Vec<usize>, EncodeError> {
+        outputs
+            .into_iter()
+            .map(|output| self.encode(output))
+            .collect()
+    }
+
+    /// Real-time commands encoded since the last call, in order. Always empty without
+    /// [`Encoder::with_realtime_queue`].
+    pub fn take_realtime(&mut self) -> Vec<u8> {
+        self.realtime
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Real-time commands encoded and not taken yet, or `None` without
+    /// [`Encoder::with_realtime_queue`].
+    pub fn realtime_mut(&mut self) -> Option<&mut Vec<u8>> {
+        self.realtime.as_mut()
+    }
+
+    /// Total number of bytes written to the writer so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Encode all outputs into a single buffer.
+pub fn to_bytes<'o>(outputs: impl IntoIterator<Item = &'o Output>) -> Result<Vec<u8>, EncodeError> {
+    let mut encoder = Encoder::new(Vec::new());
+    encoder.encode_all(outputs)?;
+    Ok(encoder.into_inner())
+}
+
+/// Encode all outputs into frames of at most `limit` bytes, for transports with a frame size
+/// limit.
+///
+/// Frames only end between outputs, so a printer never sees half a command at the end of a
+/// frame. A single command longer than `limit` is sent in a frame of its own. Only text longer
+/// than `limit` is broken up, and never inside a UTF-8 character; in a single-byte code page
+/// every byte is a character anyway.
+pub fn to_chunks<'o>(
+    outputs: impl IntoIterator<Item = &'o Output>,
+    limit: usize,
+) -> Result<Vec<Vec<u8>>, EncodeError> {
+    let limit = limit.max(1);
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::with_capacity(limit);
+    let mut bytes = Vec::new();
+
+    for output in outputs {
+        bytes.clear();
+        output.write_to(&mut bytes)?;
+
+        if chunk.len() + bytes.len() > limit && !chunk.is_empty() {
+            chunks.push(std::mem::replace(&mut chunk, Vec::with_capacity(limit)));
+        }
+
+        let mut rest = &bytes[..];
+        if let Output::Text(_) = output {
+            while rest.len() > limit {
+                let (head, tail) = rest.split_at(char_boundary(rest, limit));
+                chunks.push(head.to_vec());
+                rest = tail;
+            }
+        }
+        chunk.extend_from_slice(rest);
+    }
+
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    Ok(chunks)
+}
+
+/// The last index up to `limit` that doesn't fall inside a UTF-8 character of `text`, or `limit`
+/// if there is none. `text` must be longer than `limit`.
+fn char_boundary(text: &[u8], limit: usize) -> usize {
+    (1..=limit)
+        .rev()
+        .find(|&i| text[i] & 0xC0 != 0x80)
+        .unwrap_or(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::Command;
+
+    #[test]
+    fn encode_all_queues_realtime_commands() {
+        let outputs = [
+            Output::Text(b"ab".to_vec()),
+            Output::Command(Command::ExecutePowerOffSequence),
+            Output::Command(Command::InitializePrinter),
+        ];
+
+        let mut encoder = Encoder::new(Vec::new()).with_realtime_queue();
+        assert_eq!(encoder.encode_all(&outputs).unwrap(), [2, 5, 2]);
+        assert_eq!(encoder.position(), 4);
+        assert_eq!(encoder.take_realtime(), b"\x10\x14\x02\x01\x08");
+        assert_eq!(encoder.into_inner(), b"ab\x1b@");
+    }
+
+    #[test]
+    fn chunks_end_between_outputs() {
+        let outputs = [
+            Output::Text(b"abc".to_vec()),
+            Output::Command(Command::InitializePrinter),
+            Output::Text(b"defg".to_vec()),
+            Output::Command(Command::InitializePrinter),
+        ];
+
+        let chunks = to_chunks(&outputs, 5).unwrap();
+        assert_eq!(chunks, [&b"abc\x1b@"[..], b"defg", b"\x1b@"]);
+    }
+
+    #[test]
+    fn long_text_breaks_between_characters() {
+        let outputs = [
+            Output::Raw(b'x'),
+            Output::Text("aéé".as_bytes().to_vec()),
+            Output::Text(b"b".to_vec()),
+        ];
+
+        let chunks = to_chunks(&outputs, 2).unwrap();
+        assert_eq!(
+            chunks,
+            [&b"x"[..], b"a", "é".as_bytes(), "é".as_bytes(), b"b"]
+        );
+    }
+}
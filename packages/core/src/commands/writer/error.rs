@@ -0,0 +1,47 @@
+use std::fmt;
+
+use crate::commands::{CommandDiscriminants, validation::RangeError};
+
+#[derive(Debug)]
+pub enum EncodeError {
+    Io(std::io::Error),
+    /// A parameter holds a value the command has no encoding for.
+    OutOfRange(&'static str),
+    /// The command failed [`Command::validate`](crate::commands::Command::validate).
+    Invalid(RangeError),
+    /// The command is known but has no encoder yet.
+    Unsupported(CommandDiscriminants),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to write command: {e}"),
+            Self::OutOfRange(parameter) => write!(f, "{parameter} is out of range"),
+            Self::Invalid(e) => write!(f, "{e}"),
+            Self::Unsupported(command) => write!(f, "encoding {command:?} is not supported"),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Invalid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for EncodeError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<RangeError> for EncodeError {
+    fn from(e: RangeError) -> Self {
+        Self::Invalid(e)
+    }
+}
@@ -1,39 +1,71 @@
-use std::io::Write;
+pub mod encoder;
+pub mod error;
 
-use strum::EnumMessage;
+use std::io::Write;
 
 use crate::{
-    commands::Command,
-    types::{
-        batch_print::{BatchPrintDirection, BatchPrintMode},
-        cut_mode::{CutMode, CuttingShape},
-        pulse_info::PulseConnector,
-    },
+    commands::{Command, writer::error::EncodeError},
+    types::batch_print::{BatchPrintDirection, BatchPrintMode},
 };
 
+/// Writes every byte it is given and reports how many that was.
+struct Sink<'w, W: Write>(&'w mut W);
+
+impl<W: Write> Sink<'_, W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<usize, EncodeError> {
+        self.0.write_all(bytes)?;
+        Ok(bytes.len())
+    }
+}
+
 impl Command {
-    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<usize, std::io::Error> {
+    /// Encode the command, returning the number of bytes written.
+    ///
+    /// Any command produced by [`Command::parse`] encodes back to bytes that parse to an equal
+    /// command (in the same [`Mode`](crate::commands::reader::state::Mode)).
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<usize, EncodeError> {
+        let w = &mut Sink(w);
+
         match self {
             // Plain
-            Self::HorizontalTab => w.write(&[b'\t']),
-            Self::LineFeed => w.write(&[b'\n']),
-            Self::CarriageReturn => w.write(&[b'\r']),
-
             Self::EndJob => w.write(&[0x0C]),
             Self::EndPage => w.write(&[0x0C]),
             Self::CancelPrintDataInPageMode => w.write(&[0x18]),
 
             // ESC
-            Self::PrintDataInPageMode => w.write(&[0x1B, 0x0C]),
-            Self::SetRightSideCharacterSpacing(s) => w.write(&[0x1B, 0x20, *s]),
-            Self::SelectPrintMode(m) => w.write(&[0x1B, 0x21, m.into_bits()]),
-            Self::SetAbsolutePrintPosition(pos) => {
-                w.write(&[[0x1B, 0x24], pos.to_le_bytes()].concat())
-            }
-            Self::SelectCancelUserDefinedCharacterSet(b) => {
-                w.write(&[0x1B, 0x25, if *b { 1 } else { 0 }])
+            Self::DefineUserDefinedCharacters(characters) => {
+                let (Some(first), Some(last)) = (characters.first(), characters.last()) else {
+                    return Err(EncodeError::OutOfRange("user-defined character range"));
+                };
+
+                // Counted in u16 so that a range ending at 255 doesn't overflow.
+                let consecutive = characters
+                    .iter()
+                    .zip(first.character() as u16..)
+                    .all(|(c, expected)| c.character() as u16 == expected);
+                if !consecutive {
+                    return Err(EncodeError::OutOfRange("user-defined character range"));
+                }
+
+                let height = first.height();
+                if height == 0 || height % 8 != 0 || characters.iter().any(|c| c.height() != height)
+                {
+                    return Err(EncodeError::OutOfRange("user-defined character height"));
+                }
+
+                let mut bytes = vec![
+                    0x1B,
+                    0x26,
+                    (height / 8) as u8,
+                    first.character(),
+                    last.character(),
+                ];
+                for c in characters {
+                    bytes.push(c.width());
+                    bytes.extend(c.to_data());
+                }
+                w.write(&bytes)
             }
-            Self::DefineUserDefinedCharacters(c) => todo!(),
             Self::SpecifyBatchPrint(m, d) => w.write(&[
                 0x1B,
                 0x28,
@@ -49,86 +81,47 @@ impl Command {
                     BatchPrintDirection::Reverse => 1,
                 },
             ]),
-            Self::SelectBitImageMode(band) => todo!(),
-            Self::TurnUnderlineModeOnOff(v) => w.write(&[0x1B, 0x2D, *v]),
-            Self::SelectDefaultLineSpacing => w.write(&[0x1B, 0x32]),
-            Self::SetLineSpacing(v) => w.write(&[0x1B, 0x33, *v]),
-            Self::ReturnHome => w.write(&[0x1B, 0x3C]),
-            Self::SelectPeripheralDevice(v) => w.write(&[0x1B, 0x3D]),
-            Self::CancelUserDefinedCharacters(c) => w.write(&[0x1B, 0x3F, *c]),
-            Self::InitializePrinter => w.write(&[0x1B, 0x40]),
-            Self::TurnEmphasizedModeOnOff(b) => w.write(&[0x1B, 0x45, if *b { 1 } else { 0 }]),
-            Self::TurnDoubleStrikeModeOnOff(b) => w.write(&[0x1B, 0x46, if *b { 1 } else { 0 }]),
-            Self::PrintAndFeedPaper(v) => w.write(&[0x1B, 0x41, *v]),
-            Self::SelectPageMode => w.write(&[0x1B, 0x4B]),
-            Self::SelectCharacterFont(f) => w.write(&[0x1B, 0x4D, *f as u8]),
-            Self::SelectInternationalCharacterSet(f) => w.write(&[0x1B, 0x52, *f as u8]),
-            Self::SelectStandardMode => w.write(&[0x1B, 0x53]),
-            Self::SelectPrintDirectionInPageMode(d) => w.write(&[0x1B, 0x54, *d as u8]),
-            Self::Turn90ClockwiseRotationModeOnOff(v) => w.write(&[0x1B, 0x56, *v]),
-            Self::SetPrintAreaInPageMode(m) => todo!(),
-            Self::SetRelativePrintPosition(i) => w.write(&[[0x1B, 0x58], i.to_le_bytes()].concat()),
-            Self::SelectJustification(j) => w.write(&[0x1B, 0x61, *j as u8]),
-            Self::SelectPaperSensorToOutputPaperEndSignals(v) => w.write(&[0x1B, 0x63, b'3', *v]),
-            Self::SelectPaperSensorToStopPrinting(v) => w.write(&[0x1B, 0x63, b'4', *v]),
-            Self::EnableDisablePanelButtons(b) => {
-                w.write(&[0x1B, 0x63, b'5', if *b { 1 } else { 0 }])
+            Self::SelectBitImageMode(band) => w.write(
+                &[
+                    &[0x1B, 0x2A, band.mode().to_byte()][..],
+                    &band.width().to_le_bytes(),
+                    &band.to_data(),
+                ]
+                .concat(),
+            ),
+            Self::TurnUnderlineModeOnOff(3..) => Err(EncodeError::OutOfRange("underline mode")),
+            Self::Turn90ClockwiseRotationModeOnOff(3..) => {
+                Err(EncodeError::OutOfRange("90° clockwise rotation mode"))
             }
-            Self::PrintAndFeedNLines(v) => w.write(&[0x1B, 0x64, *v]),
-            Self::PrintAndReverseFeedNLines(v) => w.write(&[0x1B, 0x65, *v]),
-            Self::PartialCutOne => w.write(&[0x1B, 0x69]),
-            Self::PartialCutThree => w.write(&[0x1B, 0x6D]),
-            Self::GeneratePulse(c, t1, t2) => w.write(&[
-                0x1B,
-                0x70,
-                match c {
-                    PulseConnector::Pin2 => 0,
-                    PulseConnector::Pin5 => 1,
-                },
-                *t1,
-                *t2,
-            ]),
-            Self::SelectPrintColor(c) => w.write(&[0x1B, 0x72, *c as u8]),
-            Self::SelectCharacterCodeTable(t) => w.write(&[0x1B, 0x74, *t as u8]),
             Self::TransmitPeripheralDeviceStatus => w.write(&[0x1B, 0x75, 0x00]),
-            Self::TurnUpsideDownPrintModeOnOff(b) => w.write(&[0x1B, 0x76, if *b { 1 } else { 0 }]),
-
-            // GS (partial)
-            Self::SelectCharacterSize(a, b) => w.write(&[0x1D, 0x21, ((a - 1) << 4) | (b - 1)]),
-            Self::SelectCutModeAndCutPaper(mode) => {
-                match mode {
-                    CutMode::Cut(shape) => match shape {
-                        CuttingShape::Full => w.write(&[0x1D, 0x56, 0x00]), // or b'0'
-                        CuttingShape::Partial => w.write(&[0x1D, 0x56, 0x01]), // or b'1'
-                    },
-
-                    CutMode::FeedAndCut(n, shape) => {
-                        let m = match shape {
-                            CuttingShape::Full => b'A',
-                            CuttingShape::Partial => b'B',
-                        };
-                        w.write(&[0x1D, 0x56, m, *n])
-                    }
 
-                    CutMode::SetCuttingPosition(n, shape) => {
-                        let m = match shape {
-                            CuttingShape::Full => b'a',
-                            CuttingShape::Partial => b'b',
-                        };
-                        w.write(&[0x1D, 0x56, m, *n])
-                    }
+            // DLE
+            Self::RealtimeGeneratePulse(pulse) if !(1..=8).contains(&pulse.time()) => {
+                Err(EncodeError::OutOfRange("pulse time"))
+            }
 
-                    CutMode::FeedAndCutAndMoveToStart(n, shape) => {
-                        let m = match shape {
-                            CuttingShape::Full => b'g',
-                            CuttingShape::Partial => b'h',
-                        };
-                        w.write(&[0x1D, 0x56, m, *n])
-                    }
+            // GS
+            Self::SelectCharacterSize(width, height)
+                if !(1..=8).contains(width) || !(1..=8).contains(height) =>
+            {
+                Err(EncodeError::OutOfRange("character size"))
+            }
+            Self::EnableDisableRealTimeCommand(settings) => {
+                let len = (1 + settings.len() * 2) as u16;
+                let mut bytes = [&[0x1D, 0x28, 0x44][..], &len.to_le_bytes(), &[20]].concat();
+                for setting in settings {
+                    bytes.extend_from_slice(&[
+                        setting.kind as u8,
+                        if setting.enabled { 1 } else { 0 },
+                    ]);
                 }
+                w.write(&bytes)
             }
 
-            c => unimplemented!("Command {:?} not implemented", c.get_message()),
+            c => match c.encode_declared() {
+                Some(bytes) => w.write(&bytes),
+                None => Err(EncodeError::Unsupported(c.into())),
+            },
         }
     }
 }
@@ -1,3 +1,178 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::commands::{
+    RealtimeCommand, RealtimeCommandDiscriminants,
+    reader::Output,
+    writer::{encoder::Encoder, error::EncodeError},
+};
+
+/// A connection to a printer, e.g. a serial port or a network socket.
 pub trait Endpoint {
-    
+    type Error;
+
+    /// Send data that goes through the printer's receive buffer, after everything sent before.
+    fn send(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Send real-time commands ahead of anything the endpoint still holds from
+    /// [`Endpoint::send`].
+    ///
+    /// The printer processes real-time commands as soon as they arrive, so endpoints that don't
+    /// queue data can send them like any other.
+    fn send_realtime(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.send(bytes)
+    }
+}
+
+#[derive(Debug)]
+pub enum SendError<E> {
+    Encode(EncodeError),
+    Endpoint(E),
+    /// The real-time command was sent before and the printer hasn't answered it yet.
+    AwaitingReply(RealtimeCommandDiscriminants),
+}
+
+impl<E: fmt::Display> fmt::Display for SendError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "{e}"),
+            Self::Endpoint(e) => write!(f, "failed to send: {e}"),
+            Self::AwaitingReply(command) => {
+                write!(f, "{command:?} is still awaiting its reply")
+            }
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for SendError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Encode(e) => Some(e),
+            Self::Endpoint(e) => Some(e),
+            Self::AwaitingReply(_) => None,
+        }
+    }
+}
+
+impl<E> From<EncodeError> for SendError<E> {
+    fn from(e: EncodeError) -> Self {
+        Self::Encode(e)
+    }
+}
+
+/// Sends outputs to an [`Endpoint`]: real-time commands right away through its priority path,
+/// everything else through the receive buffer on [`Connection::flush`].
+///
+/// Real-time commands the printer answers are refused with [`SendError::AwaitingReply`] until
+/// their reply is marked with [`Connection::reply_received`].
+pub struct Connection<E: Endpoint> {
+    endpoint: E,
+    encoder: Encoder<Vec<u8>>,
+    /// Real-time commands encoded but not sent yet, because the endpoint failed.
+    unsent: Vec<RealtimeCommandDiscriminants>,
+    awaiting: VecDeque<RealtimeCommandDiscriminants>,
+}
+
+impl<E: Endpoint> Connection<E> {
+    pub fn new(endpoint: E) -> Self {
+        Self {
+            endpoint,
+            encoder: Encoder::new(Vec::new()).with_realtime_queue(),
+            unsent: Vec::new(),
+            awaiting: VecDeque::new(),
+        }
+    }
+
+    /// Check every command with [`Command::validate`] before sending it.
+    ///
+    /// [`Command::validate`]: crate::commands::Command::validate
+    pub fn with_validation(self) -> Self {
+        Self {
+            encoder: self.encoder.with_validation(),
+            ..self
+        }
+    }
+
+    /// Send a real-time command right away, or queue anything else until the next
+    /// [`Connection::flush`]. A real-time command the endpoint fails to send stays queued, and
+    /// goes ahead of the next one or of the next flush.
+    pub fn send(&mut self, output: &Output) -> Result<(), SendError<E::Error>> {
+        let realtime = match output {
+            Output::Command(command) => command.as_realtime(),
+            _ => None,
+        };
+
+        if let Some(command) = realtime
+            && command.expects_reply()
+            && (self.awaiting.contains(&command) || self.unsent.contains(&command))
+        {
+            return Err(SendError::AwaitingReply(command));
+        }
+
+        self.encoder.encode(output)?;
+
+        if let Some(command) = realtime {
+            self.unsent.push(command);
+            self.send_unsent_realtime()?;
+        }
+
+        Ok(())
+    }
+
+    fn send_unsent_realtime(&mut self) -> Result<(), SendError<E::Error>> {
+        let Some(bytes) = self.encoder.realtime_mut().filter(|b| !b.is_empty()) else {
+            return Ok(());
+        };
+
+        self.endpoint
+            .send_realtime(bytes)
+            .map_err(SendError::Endpoint)?;
+        bytes.clear();
+
+        let replies = self.unsent.drain(..).filter(|c| c.expects_reply());
+        self.awaiting.extend(replies);
+        Ok(())
+    }
+
+    pub fn send_realtime(&mut self, command: &RealtimeCommand) -> Result<(), SendError<E::Error>> {
+        self.send(&Output::Command(command.clone().into()))
+    }
+
+    /// Send everything queued since the last flush, after any real-time commands still unsent.
+    /// If the endpoint fails, the queue is kept so the flush can be retried.
+    pub fn flush(&mut self) -> Result<(), SendError<E::Error>> {
+        self.send_unsent_realtime()?;
+
+        let bytes = self.encoder.get_mut();
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        self.endpoint.send(bytes).map_err(SendError::Endpoint)?;
+        bytes.clear();
+        Ok(())
+    }
+
+    /// Real-time commands sent and not answered yet, oldest first.
+    pub fn awaiting_reply(&self) -> impl Iterator<Item = RealtimeCommandDiscriminants> + '_ {
+        self.awaiting.iter().copied()
+    }
+
+    /// Mark the oldest command awaiting a reply as answered, returning it. The printer answers
+    /// real-time commands in the order it receives them.
+    pub fn reply_received(&mut self) -> Option<RealtimeCommandDiscriminants> {
+        self.awaiting.pop_front()
+    }
+
+    pub fn endpoint(&self) -> &E {
+        &self.endpoint
+    }
+
+    pub fn endpoint_mut(&mut self) -> &mut E {
+        &mut self.endpoint
+    }
+
+    /// The endpoint, dropping anything not flushed yet.
+    pub fn into_inner(self) -> E {
+        self.endpoint
+    }
 }
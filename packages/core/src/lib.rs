@@ -1,5 +1,7 @@
 use crate::commands::Command;
 
+pub use thermal_escpos_macros::escpos;
+
 pub mod commands;
 pub mod emulator;
 pub mod state;
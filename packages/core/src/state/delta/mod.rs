@@ -1,22 +1,14 @@
 use std::slice::Iter;
 
-use crate::{
-    state::effect::{Effect, IntoEffects},
-    types::{
-        character_set::{AsciiVariant, Codepage},
-        font::Font,
-        justification::Justification,
-    },
+use crate::state::{
+    State,
+    effect::{Effect, IntoEffects},
 };
 
+/// What to print, in order: effects interleaved with the state transitions between them.
 #[derive(Default, Debug)]
 pub struct Delta {
-    effects: Vec<Effect>,
-    pub apply_font: Option<Font>,
-    pub apply_ascii_variant: Option<AsciiVariant>,
-    pub apply_codepage: Option<Codepage>,
-    pub apply_justification: Option<Justification>,
-    pub apply_text_scale: Option<(u8, u8)>,
+    pub(crate) effects: Vec<Effect>,
 }
 
 impl Delta {
@@ -39,28 +31,38 @@ impl Delta {
         self.effects.iter()
     }
 
-    /// Merge `other` into `self`, returning the combined delta.
-    /// - `effects` are concatenated (self’s first, then other’s).
-    /// - For `apply_*`, the last non-None seen wins.
+    /// Merge `other` into `self`, returning the combined delta: self's entries first, then
+    /// other's, state transitions included.
     pub fn merged_with(mut self, other: Delta) -> Delta {
         self.effects.extend(other.effects);
-        if other.apply_font.is_some() {
-            self.apply_font = other.apply_font;
-        }
-        if other.apply_ascii_variant.is_some() {
-            self.apply_ascii_variant = other.apply_ascii_variant;
-        }
-        if other.apply_codepage.is_some() {
-            self.apply_codepage = other.apply_codepage;
-        }
-        if other.apply_justification.is_some() {
-            self.apply_justification = other.apply_justification
-        }
-        if other.apply_text_scale.is_some() {
-            self.apply_text_scale = other.apply_text_scale
-        }
         self
     }
+
+    /// `self` with every run of consecutive state transitions merged into one, the last mode set
+    /// winning. Nothing is printed between them, so the output is the same.
+    pub fn coalesced(self) -> Delta {
+        let mut effects: Vec<Effect> = vec![];
+        for effect in self.effects {
+            match (effects.last_mut(), effect) {
+                (Some(Effect::SetState(last)), Effect::SetState(state)) => {
+                    *last = std::mem::take(last).merged_with(state);
+                }
+                (_, effect) => effects.push(effect),
+            }
+        }
+        Delta { effects }
+    }
+
+    /// Every mode the delta's transitions set, the last one winning.
+    pub fn state(&self) -> State {
+        self.effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::SetState(state) => Some(state.clone()),
+                _ => None,
+            })
+            .fold(State::default(), State::merged_with)
+    }
 }
 
 impl std::ops::Add for Delta {
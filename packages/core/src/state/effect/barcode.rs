@@ -0,0 +1,385 @@
+use std::{fmt, iter::once};
+
+use crate::{
+    state::effect::{Effect, IntoEffects},
+    types::{
+        barcode::{BarcodeData, BarcodeSystem, HriPosition},
+        font::Font,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbology {
+    UpcA,
+    UpcE,
+    /// EAN-13, also JAN-13.
+    Ean13,
+    /// EAN-8, also JAN-8.
+    Ean8,
+    Code39,
+    /// Interleaved 2 of 5.
+    Itf,
+    /// Codabar, also NW-7.
+    Codabar,
+    Code93,
+    /// Any ASCII text; the code sets are switched as needed.
+    Code128,
+}
+
+impl Symbology {
+    pub fn system(self) -> BarcodeSystem {
+        match self {
+            Symbology::UpcA => BarcodeSystem::UpcA,
+            Symbology::UpcE => BarcodeSystem::UpcE,
+            Symbology::Ean13 => BarcodeSystem::Ean13,
+            Symbology::Ean8 => BarcodeSystem::Ean8,
+            Symbology::Code39 => BarcodeSystem::Code39,
+            Symbology::Itf => BarcodeSystem::Itf,
+            Symbology::Codabar => BarcodeSystem::Codabar,
+            Symbology::Code93 => BarcodeSystem::Code93,
+            Symbology::Code128 => BarcodeSystem::Code128,
+        }
+    }
+}
+
+/// Why data can't be printed as a barcode of its symbology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarcodeError {
+    /// The data has `len` characters, `expected` describes what the symbology takes.
+    Length {
+        symbology: Symbology,
+        len: usize,
+        expected: &'static str,
+    },
+    /// A character the symbology can't encode, or one out of place.
+    Character {
+        symbology: Symbology,
+        character: char,
+    },
+    /// The data ends in a check digit that doesn't match the rest.
+    CheckDigit {
+        symbology: Symbology,
+        expected: char,
+        found: char,
+    },
+}
+
+impl fmt::Display for BarcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length {
+                symbology,
+                len,
+                expected,
+            } => write!(f, "{symbology:?} takes {expected}, not {len}"),
+            Self::Character {
+                symbology,
+                character,
+            } => write!(f, "{symbology:?} can't encode {character:?} there"),
+            Self::CheckDigit {
+                symbology,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{symbology:?} check digit is {found}, should be {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BarcodeError {}
+
+#[derive(Debug, Clone)]
+pub struct Barcode {
+    pub symbology: Symbology,
+    pub data: String,
+    /// Height in dots, `GS h`.
+    pub height: u8,
+    /// Width of the narrowest bar in dots, `GS w`.
+    pub module_width: u8,
+    pub hri_position: HriPosition,
+    pub hri_font: Font,
+}
+
+impl Barcode {
+    pub fn new(symbology: Symbology, data: &str) -> Barcode {
+        Barcode {
+            symbology,
+            data: data.to_string(),
+            height: 162,
+            module_width: 3,
+            hri_position: HriPosition::Below,
+            hri_font: Font::A,
+        }
+    }
+
+    pub fn with_height(mut self, height: u8) -> Barcode {
+        self.height = height;
+        self
+    }
+
+    pub fn with_module_width(mut self, module_width: u8) -> Barcode {
+        self.module_width = module_width;
+        self
+    }
+
+    pub fn with_hri_position(mut self, hri_position: HriPosition) -> Barcode {
+        self.hri_position = hri_position;
+        self
+    }
+
+    pub fn with_hri_font(mut self, hri_font: Font) -> Barcode {
+        self.hri_font = hri_font;
+        self
+    }
+
+    /// The `GS k` data for the barcode, after checking it against its symbology. EAN and UPC
+    /// data get their check digit appended if it's left out.
+    pub fn encode(&self) -> Result<BarcodeData, BarcodeError> {
+        let symbology = self.symbology;
+        let data = self.data.as_bytes();
+
+        let length = |expected: &'static str| BarcodeError::Length {
+            symbology,
+            len: self.data.chars().count(),
+            expected,
+        };
+        let only = |allowed: &dyn Fn(u8) -> bool| match self
+            .data
+            .chars()
+            .find(|c| !c.is_ascii() || !allowed(*c as u8))
+        {
+            Some(character) => Err(BarcodeError::Character {
+                symbology,
+                character,
+            }),
+            None => Ok(()),
+        };
+
+        let encoded = match symbology {
+            Symbology::UpcA => with_check_digit(symbology, data, 11, "11 or 12 digits")?,
+            Symbology::Ean13 => with_check_digit(symbology, data, 12, "12 or 13 digits")?,
+            Symbology::Ean8 => with_check_digit(symbology, data, 7, "7 or 8 digits")?,
+            Symbology::UpcE => upc_e(data)?,
+            Symbology::Code39 => {
+                let inner = match data {
+                    [b'*', inner @ .., b'*'] => inner,
+                    _ => data,
+                };
+                if let Some(&c) = inner.iter().find(|c| **c == b'*') {
+                    return Err(BarcodeError::Character {
+                        symbology,
+                        character: c as char,
+                    });
+                }
+                only(&|c| {
+                    c.is_ascii_digit() || c.is_ascii_uppercase() || b" $%*+-./".contains(&c)
+                })?;
+                if inner.is_empty() {
+                    return Err(length("at least 1 character"));
+                }
+                data.to_vec()
+            }
+            Symbology::Itf => {
+                only(&|c| c.is_ascii_digit())?;
+                if data.is_empty() || !data.len().is_multiple_of(2) {
+                    return Err(length("an even number of digits"));
+                }
+                data.to_vec()
+            }
+            Symbology::Codabar => {
+                let start_stop = |c: u8| b"ABCDabcd".contains(&c);
+                only(&|c| start_stop(c) || c.is_ascii_digit() || b"$+-./:".contains(&c))?;
+                let [first, inner @ .., last] = data else {
+                    return Err(length("a start and a stop character"));
+                };
+                if let Some(&c) = [first, last]
+                    .into_iter()
+                    .find(|c| !start_stop(**c))
+                    .or_else(|| inner.iter().find(|c| start_stop(**c)))
+                {
+                    return Err(BarcodeError::Character {
+                        symbology,
+                        character: c as char,
+                    });
+                }
+                data.to_vec()
+            }
+            Symbology::Code93 => {
+                only(&|_| true)?;
+                if data.is_empty() {
+                    return Err(length("at least 1 character"));
+                }
+                data.to_vec()
+            }
+            Symbology::Code128 => {
+                only(&|_| true)?;
+                if data.is_empty() {
+                    return Err(length("at least 1 character"));
+                }
+                code128(data)
+            }
+        };
+
+        if encoded.len() > u8::MAX as usize {
+            return Err(length("at most 255 bytes once encoded"));
+        }
+
+        Ok(BarcodeData::new(symbology.system(), encoded))
+    }
+}
+
+impl IntoEffects for Barcode {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::Barcode(self))
+    }
+}
+
+/// The EAN/UPC check digit for `digits`, weighting them 3, 1, 3, … from the right.
+fn check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| u32::from(d - b'0') * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+
+    b'0' + ((10 - sum % 10) % 10) as u8
+}
+
+/// `data` of `len` digits with its check digit appended, or of `len + 1` with a valid one.
+fn with_check_digit(
+    symbology: Symbology,
+    data: &[u8],
+    len: usize,
+    expected: &'static str,
+) -> Result<Vec<u8>, BarcodeError> {
+    if let Some(&c) = data.iter().find(|c| !c.is_ascii_digit()) {
+        return Err(BarcodeError::Character {
+            symbology,
+            character: c as char,
+        });
+    }
+
+    if data.len() == len {
+        return Ok([data, &[check_digit(data)]].concat());
+    }
+
+    if data.len() != len + 1 {
+        return Err(BarcodeError::Length {
+            symbology,
+            len: data.len(),
+            expected,
+        });
+    }
+
+    let (digits, &[found]) = data.split_at(len) else {
+        unreachable!("split one digit off")
+    };
+    let expected = check_digit(digits);
+    if found != expected {
+        return Err(BarcodeError::CheckDigit {
+            symbology,
+            expected: expected as char,
+            found: found as char,
+        });
+    }
+
+    Ok(data.to_vec())
+}
+
+/// UPC-E as `0`, the six digits and the check digit of the UPC-A code it's short for. Takes the
+/// six digits alone, or with the number system 0 in front and optionally the check digit.
+fn upc_e(data: &[u8]) -> Result<Vec<u8>, BarcodeError> {
+    let symbology = Symbology::UpcE;
+    if let Some(&c) = data.iter().find(|c| !c.is_ascii_digit()) {
+        return Err(BarcodeError::Character {
+            symbology,
+            character: c as char,
+        });
+    }
+
+    let (six, check) = match data.len() {
+        6 => (data, None),
+        7 | 8 if data[0] == b'0' => (&data[1..7], data.get(7).copied()),
+        7 | 8 => {
+            return Err(BarcodeError::Character {
+                symbology,
+                character: data[0] as char,
+            });
+        }
+        len => {
+            return Err(BarcodeError::Length {
+                symbology,
+                len,
+                expected: "6, 7 or 8 digits",
+            });
+        }
+    };
+
+    let [d1, d2, d3, d4, d5, d6] = [six[0], six[1], six[2], six[3], six[4], six[5]];
+    let upc_a: [u8; 11] = match d6 {
+        b'0'..=b'2' => [b'0', d1, d2, d6, b'0', b'0', b'0', b'0', d3, d4, d5],
+        b'3' => [b'0', d1, d2, d3, b'0', b'0', b'0', b'0', b'0', d4, d5],
+        b'4' => [b'0', d1, d2, d3, d4, b'0', b'0', b'0', b'0', b'0', d5],
+        _ => [b'0', d1, d2, d3, d4, d5, b'0', b'0', b'0', b'0', d6],
+    };
+    let expected = check_digit(&upc_a);
+
+    if let Some(found) = check
+        && found != expected
+    {
+        return Err(BarcodeError::CheckDigit {
+            symbology,
+            expected: expected as char,
+            found: found as char,
+        });
+    }
+
+    Ok([&[b'0'][..], six, &[expected]].concat())
+}
+
+/// CODE128 data with the `{A`, `{B` and `{C` code set selections the printer needs: code set
+/// C for runs of at least four digits, A for control characters and B for everything else.
+fn code128(text: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    let mut set = None;
+    let select = |out: &mut Vec<u8>, set: &mut Option<u8>, code_set: u8| {
+        if *set != Some(code_set) {
+            out.extend([b'{', code_set]);
+            *set = Some(code_set);
+        }
+    };
+
+    let mut i = 0;
+    while i < text.len() {
+        let digits = text[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if digits >= 4 || (digits >= 2 && digits == text.len()) {
+            select(&mut out, &mut set, b'C');
+            // Code set C packs each pair of digits into a single value 0–99
+            for pair in text[i..i + digits / 2 * 2].chunks(2) {
+                out.push((pair[0] - b'0') * 10 + (pair[1] - b'0'));
+            }
+            i += digits / 2 * 2;
+            continue;
+        }
+
+        let c = text[i];
+        let code_set = match (c, set) {
+            (0..=31, _) => b'A',
+            (96.., _) => b'B',
+            (_, Some(b'A')) => b'A',
+            _ => b'B',
+        };
+        select(&mut out, &mut set, code_set);
+        out.push(c);
+        // `{` starts a code set selection, so a literal one is doubled
+        if c == b'{' {
+            out.push(c);
+        }
+        i += 1;
+    }
+
+    out
+}
@@ -0,0 +1,345 @@
+use std::iter::once;
+
+use crate::{
+    state::effect::{Effect, IntoEffects},
+    types::bit_image_band::{BitImageBand, BitImageMode},
+};
+
+/// How grey levels are turned into printed and blank dots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// Print every pixel darker than the threshold.
+    Threshold,
+    /// Spread each pixel's error over its neighbours: smooth gradients, fine grain.
+    #[default]
+    FloydSteinberg,
+    /// Spread three quarters of the error a little further: more contrast, cleaner whites.
+    Atkinson,
+}
+
+/// How an image is fitted to the printable width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scaling {
+    /// One pixel per dot. Anything past the printable width is cut off by the printer.
+    Original,
+    /// Scale down to the printable width if it's wider.
+    #[default]
+    Shrink,
+    /// Scale up or down to exactly the printable width.
+    Fit,
+}
+
+/// The command an image is sent with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageEncoding {
+    /// `GS v 0`, a raster bit image.
+    Raster,
+    /// `GS ( L` functions 112 and 50, graphics stored in and printed from the print buffer.
+    Graphics,
+    /// `ESC *` bands of 24 dots, for models that predate the other two.
+    BitImage,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    /// Row-major, 0 black to 255 white.
+    luma: Vec<u8>,
+    pub dither: Dither,
+    /// Grey level below which a pixel counts as black.
+    pub threshold: u8,
+    pub scaling: Scaling,
+    /// `None` to let the emulator pick what its model supports best.
+    pub encoding: Option<ImageEncoding>,
+}
+
+impl Image {
+    /// An image from 8-bit grey levels, 0 black to 255 white.
+    ///
+    /// Panics if `luma` doesn't hold `width` × `height` pixels.
+    pub fn from_grayscale(width: usize, height: usize, luma: Vec<u8>) -> Image {
+        assert_eq!(luma.len(), width * height, "grayscale buffer size");
+
+        Image {
+            width,
+            height,
+            luma,
+            dither: Dither::default(),
+            threshold: 128,
+            scaling: Scaling::default(),
+            encoding: None,
+        }
+    }
+
+    /// An image from 8-bit RGBA pixels, composited over white paper.
+    ///
+    /// Panics if `rgba` doesn't hold `width` × `height` pixels.
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Image {
+        assert_eq!(rgba.len(), width * height * 4, "RGBA buffer size");
+
+        let luma = rgba
+            .chunks_exact(4)
+            .map(|p| {
+                let [r, g, b, a] = [p[0], p[1], p[2], p[3]].map(u32::from);
+                // ITU-R BT.601 luma, in thousandths
+                let y = (299 * r + 587 * g + 114 * b) / 1000;
+                ((y * a + 255 * (255 - a)) / 255) as u8
+            })
+            .collect();
+
+        Self::from_grayscale(width, height, luma)
+    }
+
+    pub fn with_dither(mut self, dither: Dither) -> Image {
+        self.dither = dither;
+        self
+    }
+
+    pub fn with_threshold(mut self, threshold: u8) -> Image {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_scaling(mut self, scaling: Scaling) -> Image {
+        self.scaling = scaling;
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: ImageEncoding) -> Image {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The size after scaling for a `printable_width` dots wide print area.
+    fn scaled_size(&self, printable_width: usize) -> (usize, usize) {
+        let width = match self.scaling {
+            Scaling::Original => return (self.width, self.height),
+            Scaling::Shrink => self.width.min(printable_width),
+            Scaling::Fit => printable_width,
+        };
+
+        if self.width == 0 || width == self.width {
+            return (self.width, self.height);
+        }
+
+        (width, (self.height * width).div_ceil(self.width).max(1))
+    }
+
+    /// Grey levels resampled to `width` × `height`, each the average of the source pixels it
+    /// covers, or the nearest one when enlarging.
+    fn resized(&self, width: usize, height: usize) -> Vec<u8> {
+        if (width, height) == (self.width, self.height) {
+            return self.luma.clone();
+        }
+
+        let span = |i: usize, from: usize, into: usize| {
+            let start = i * from / into;
+            start..((i + 1) * from / into).max(start + 1)
+        };
+
+        let mut out = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let rows = span(y, self.height, height);
+            for x in 0..width {
+                let cols = span(x, self.width, width);
+                let count = rows.len() * cols.len();
+                let sum: usize = rows
+                    .clone()
+                    .flat_map(|sy| cols.clone().map(move |sx| sy * self.width + sx))
+                    .map(|i| self.luma[i] as usize)
+                    .sum();
+                out.push((sum / count) as u8);
+            }
+        }
+
+        out
+    }
+
+    /// The image scaled for a `printable_width` dots wide print area and dithered to dots.
+    pub fn to_bitmap(&self, printable_width: usize) -> Bitmap {
+        let (width, height) = self.scaled_size(printable_width);
+        let mut levels: Vec<i16> = self
+            .resized(width, height)
+            .into_iter()
+            .map(i16::from)
+            .collect();
+
+        // (dx, dy, weight): where a pixel's error goes, as a fraction of `divisor`
+        let (spread, divisor): (&[(isize, usize, i16)], i16) = match self.dither {
+            Dither::Threshold => (&[], 1),
+            Dither::FloydSteinberg => (&[(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)], 16),
+            Dither::Atkinson => (
+                &[
+                    (1, 0, 1),
+                    (2, 0, 1),
+                    (-1, 1, 1),
+                    (0, 1, 1),
+                    (1, 1, 1),
+                    (0, 2, 1),
+                ],
+                8,
+            ),
+        };
+
+        let mut dots = vec![false; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                let level = levels[y * width + x];
+                let black = level < i16::from(self.threshold);
+                dots[y * width + x] = black;
+
+                let error = level - if black { 0 } else { 255 };
+                for &(dx, dy, weight) in spread {
+                    let (nx, ny) = (x.wrapping_add_signed(dx), y + dy);
+                    if nx < width && ny < height {
+                        levels[ny * width + nx] += error * weight / divisor;
+                    }
+                }
+            }
+        }
+
+        Bitmap {
+            width,
+            height,
+            dots,
+        }
+    }
+}
+
+impl IntoEffects for Image {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::Image(self))
+    }
+}
+
+/// A dithered image, one entry per dot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    /// Row-major, `true` for a printed dot.
+    dots: Vec<bool>,
+}
+
+impl Bitmap {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Bytes per raster row.
+    pub fn row_bytes(&self) -> usize {
+        self.width.div_ceil(8)
+    }
+
+    pub fn dot(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.dots[y * self.width + x]
+    }
+
+    /// Rows `top..top + rows` in raster format: MSB leftmost, [`Bitmap::row_bytes`] per row.
+    pub fn raster(&self, top: usize, rows: usize) -> Vec<u8> {
+        let row_bytes = self.row_bytes();
+        let mut data = vec![0u8; row_bytes * rows];
+
+        for y in 0..rows {
+            for x in 0..self.width {
+                if self.dot(x, top + y) {
+                    data[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+
+        data
+    }
+
+    /// The `ESC *` band of `mode`'s height starting at row `top`, blank past the bottom.
+    pub fn band(&self, top: usize, mode: BitImageMode) -> BitImageBand {
+        let rows = mode.height_rows() as usize;
+        let canvas = (top..top + rows)
+            .flat_map(|y| (0..self.width).map(move |x| self.dot(x, y) as u8))
+            .collect();
+
+        BitImageBand::new(mode, self.width as u16, canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn black_dots(bitmap: &Bitmap) -> usize {
+        (0..bitmap.height())
+            .flat_map(|y| (0..bitmap.width()).map(move |x| bitmap.dot(x, y)))
+            .filter(|dot| *dot)
+            .count()
+    }
+
+    #[test]
+    fn rgba_is_composited_over_white() {
+        let rgba = [0, 0, 0, 255, 0, 0, 0, 0, 90, 90, 90, 255];
+        let bitmap = Image::from_rgba(3, 1, &rgba)
+            .with_dither(Dither::Threshold)
+            .to_bitmap(512);
+
+        assert_eq!([0, 1, 2].map(|x| bitmap.dot(x, 0)), [true, false, true]);
+    }
+
+    #[test]
+    fn scaling_to_the_printable_width() {
+        let size = |width, height, scaling| {
+            let bitmap = Image::from_grayscale(width, height, vec![255; width * height])
+                .with_scaling(scaling)
+                .to_bitmap(512);
+            (bitmap.width(), bitmap.height())
+        };
+
+        assert_eq!(size(1024, 300, Scaling::Original), (1024, 300));
+        assert_eq!(size(1024, 300, Scaling::Shrink), (512, 150));
+        assert_eq!(size(100, 30, Scaling::Shrink), (100, 30));
+        assert_eq!(size(100, 30, Scaling::Fit), (512, 154));
+    }
+
+    #[test]
+    fn dithering_keeps_the_grey_level() {
+        let dots = |dither| {
+            let image = Image::from_grayscale(64, 64, vec![64; 64 * 64]).with_dither(dither);
+            black_dots(&image.to_bitmap(512))
+        };
+
+        let floyd_steinberg = dots(Dither::FloydSteinberg);
+        let atkinson = dots(Dither::Atkinson);
+
+        assert_eq!(dots(Dither::Threshold), 64 * 64);
+        // A quarter of the way from black to white
+        assert!((2867..3277).contains(&floyd_steinberg), "{floyd_steinberg}");
+        // Dropping part of the error pushes dark grey further towards black
+        assert!((floyd_steinberg..64 * 64).contains(&atkinson), "{atkinson}");
+    }
+
+    #[test]
+    fn raster_rows_are_msb_first() {
+        let mut luma = vec![255; 10 * 2];
+        luma[0] = 0;
+        luma[9] = 0;
+        luma[10 + 8] = 0;
+        let bitmap = Image::from_grayscale(10, 2, luma)
+            .with_dither(Dither::Threshold)
+            .to_bitmap(512);
+
+        assert_eq!(bitmap.row_bytes(), 2);
+        assert_eq!(bitmap.raster(0, 2), [0x80, 0x40, 0x00, 0x80]);
+        assert_eq!(bitmap.raster(1, 1), [0x00, 0x80]);
+    }
+}
@@ -1,8 +1,25 @@
-use crate::state::effect::{cut::Cut, feed::Feed, print::Write};
+use crate::state::{
+    State,
+    effect::{
+        barcode::Barcode,
+        cut::Cut,
+        feed::Feed,
+        image::Image,
+        print::Write,
+        pulse::{Beep, OpenDrawer},
+        rule::Rule,
+        symbol::{DataMatrix, Pdf417, QrCode},
+    },
+};
 
+pub mod barcode;
 pub mod cut;
 pub mod feed;
+pub mod image;
 pub mod print;
+pub mod pulse;
+pub mod rule;
+pub mod symbol;
 
 /// Conceptually: Stateless commands
 #[derive(Debug, Clone)]
@@ -10,6 +27,16 @@ pub enum Effect {
     Write(Write),
     Feed(Feed),
     Cut(Cut),
+    Image(Image),
+    Barcode(Barcode),
+    QrCode(QrCode),
+    Pdf417(Pdf417),
+    DataMatrix(DataMatrix),
+    Rule(Rule),
+    OpenDrawer(OpenDrawer),
+    Beep(Beep),
+    /// Switch to the modes the state sets, leaving the rest as they are.
+    SetState(State),
 }
 
 pub trait IntoEffects {
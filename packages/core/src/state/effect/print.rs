@@ -1,58 +1,150 @@
 use std::iter::once;
 
 use crate::{
-    state::effect::{Effect, IntoEffects},
+    state::{
+        State,
+        effect::{Effect, IntoEffects},
+    },
     types::{
         character_set::{AsciiVariant, Codepage},
         font::Font,
         justification::Justification,
+        print_color::PrintColor,
     },
 };
 
+/// A run of text with its own character modes, within a line of rich text.
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub text: String,
+    pub emphasized: bool,
+    /// Underline thickness in dots, 0–2.
+    pub underline: u8,
+    /// White on black.
+    pub reverse: bool,
+    /// `None` for the scale of the [`Write`] it's in.
+    pub scale: Option<(u8, u8)>,
+    pub color: PrintColor,
+}
+
+impl Span {
+    pub fn new(text: &str) -> Span {
+        Span {
+            text: text.to_string(),
+            emphasized: false,
+            underline: 0,
+            reverse: false,
+            scale: None,
+            color: PrintColor::Black,
+        }
+    }
+
+    pub fn with_emphasized(mut self, emphasized: bool) -> Span {
+        self.emphasized = emphasized;
+        self
+    }
+
+    pub fn with_underline(mut self, underline: u8) -> Span {
+        self.underline = underline;
+        self
+    }
+
+    pub fn with_reverse(mut self, reverse: bool) -> Span {
+        self.reverse = reverse;
+        self
+    }
+
+    pub fn with_scale(mut self, x_scale: u8, y_scale: u8) -> Span {
+        self.scale = Some((x_scale, y_scale));
+        self
+    }
+
+    pub fn with_color(mut self, color: PrintColor) -> Span {
+        self.color = color;
+        self
+    }
+
+    /// The modes the span prints in, inside a [`Write`] of `scale`.
+    pub fn state(&self, scale: (u8, u8)) -> State {
+        State::default()
+            .with_emphasized(self.emphasized)
+            .with_underline(self.underline)
+            .with_reverse(self.reverse)
+            .with_text_scale(self.scale.unwrap_or(scale))
+            .with_print_color(self.color)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum WriteContents {
     Utf8(String),
     AsciiLike(Vec<u8>, AsciiVariant, Codepage),
+    /// Spans printed one after the other, switching modes in between. Modes the spans change
+    /// are switched back afterwards.
+    Rich(Vec<Span>),
 }
 
 impl WriteContents {
     pub fn from_str(v: &str) -> WriteContents {
         Self::Utf8(v.to_string())
     }
+
+    pub fn rich(spans: impl IntoIterator<Item = Span>) -> WriteContents {
+        Self::Rich(spans.into_iter().collect())
+    }
 }
 
+/// Text to print. The font, justification and scale are `None` to print in whatever the
+/// printer is set to, so that a state transition before the write stays in effect.
 #[derive(Debug, Clone)]
 pub struct Write {
     pub contents: WriteContents,
-    pub font: Font,
-    pub justification: Justification,
-    pub scale: (u8, u8),
+    pub font: Option<Font>,
+    pub justification: Option<Justification>,
+    pub scale: Option<(u8, u8)>,
 }
 
 impl Write {
     pub fn from_str(v: &str) -> Write {
         Write {
             contents: WriteContents::from_str(v),
-            font: Font::A,
-            justification: Justification::LeftJustified,
-            scale: (1, 1),
+            font: None,
+            justification: None,
+            scale: None,
+        }
+    }
+
+    pub fn rich(spans: impl IntoIterator<Item = Span>) -> Write {
+        Write {
+            contents: WriteContents::rich(spans),
+            ..Self::from_str("")
         }
     }
 
     pub fn with_font(mut self, font: Font) -> Write {
-        self.font = font;
+        self.font = Some(font);
         self
     }
 
     pub fn with_justification(mut self, justification: Justification) -> Write {
-        self.justification = justification;
+        self.justification = Some(justification);
         self
     }
 
     pub fn with_scale(mut self, x_scale: u8, y_scale: u8) -> Write {
-        self.scale = (x_scale, y_scale);
+        self.scale = Some((x_scale, y_scale));
         self
     }
+
+    /// The modes the write sets before printing.
+    pub fn state(&self) -> State {
+        State {
+            font: self.font,
+            justification: self.justification,
+            text_scale: self.scale,
+            ..State::default()
+        }
+    }
 }
 
 impl IntoEffects for Write {
@@ -60,3 +152,47 @@ impl IntoEffects for Write {
         once(Effect::Write(self))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_state_sets_every_character_mode() {
+        let span = Span::new("a")
+            .with_emphasized(true)
+            .with_underline(2)
+            .with_color(PrintColor::Red);
+
+        assert_eq!(
+            span.state((1, 1)),
+            State::default()
+                .with_emphasized(true)
+                .with_underline(2)
+                .with_reverse(false)
+                .with_text_scale((1, 1))
+                .with_print_color(PrintColor::Red)
+        );
+    }
+
+    #[test]
+    fn span_scale_overrides_the_write() {
+        assert_eq!(Span::new("a").state((2, 3)).text_scale(), Some((2, 3)));
+        assert_eq!(
+            Span::new("a").with_scale(4, 1).state((2, 3)).text_scale(),
+            Some((4, 1))
+        );
+    }
+
+    #[test]
+    fn write_state_only_sets_what_was_given() {
+        assert_eq!(Write::from_str("a").state(), State::default());
+        assert_eq!(
+            Write::rich([Span::new("a")])
+                .with_font(Font::B)
+                .with_scale(2, 2)
+                .state(),
+            State::default().with_font(Font::B).with_text_scale((2, 2))
+        );
+    }
+}
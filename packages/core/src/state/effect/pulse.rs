@@ -0,0 +1,108 @@
+use std::iter::once;
+
+use crate::{
+    state::effect::{Effect, IntoEffects},
+    types::pulse_info::PulseConnector,
+};
+
+/// `ms` in the 2 ms units of `ESC p`, rounded up and clamped to a byte.
+fn pulse_units(ms: u16) -> u8 {
+    ms.div_ceil(2).min(u8::MAX as u16) as u8
+}
+
+/// A pulse to the drawer kick-out connector.
+#[derive(Debug, Clone)]
+pub struct OpenDrawer {
+    pub connector: PulseConnector,
+    /// Up to 510 ms.
+    pub on_ms: u16,
+    /// Up to 510 ms, and at least `on_ms`.
+    pub off_ms: u16,
+}
+
+impl OpenDrawer {
+    pub fn new(connector: PulseConnector) -> OpenDrawer {
+        OpenDrawer {
+            connector,
+            on_ms: 50,
+            off_ms: 500,
+        }
+    }
+
+    pub fn with_on_ms(mut self, on_ms: u16) -> OpenDrawer {
+        self.on_ms = on_ms;
+        self
+    }
+
+    pub fn with_off_ms(mut self, off_ms: u16) -> OpenDrawer {
+        self.off_ms = off_ms;
+        self
+    }
+
+    /// The ON and OFF times in 2 ms units, clamped so that the pulse is as asked or as close
+    /// as the printer allows.
+    pub fn pulse_units(&self) -> (u8, u8) {
+        let on = pulse_units(self.on_ms);
+        (on, pulse_units(self.off_ms).max(on))
+    }
+}
+
+impl IntoEffects for OpenDrawer {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::OpenDrawer(self))
+    }
+}
+
+/// How long each beep sounds and the silence after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeepPattern {
+    pub on_ms: u16,
+    pub off_ms: u16,
+}
+
+impl BeepPattern {
+    pub const SHORT: BeepPattern = BeepPattern::new(100, 100);
+    pub const LONG: BeepPattern = BeepPattern::new(400, 400);
+
+    pub const fn new(on_ms: u16, off_ms: u16) -> BeepPattern {
+        BeepPattern { on_ms, off_ms }
+    }
+
+    /// The ON and OFF times in 2 ms units, clamped like [`OpenDrawer::pulse_units`].
+    pub fn pulse_units(&self) -> (u8, u8) {
+        let on = pulse_units(self.on_ms);
+        (on, pulse_units(self.off_ms).max(on))
+    }
+}
+
+impl Default for BeepPattern {
+    fn default() -> Self {
+        Self::SHORT
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Beep {
+    pub pattern: BeepPattern,
+    pub count: u8,
+}
+
+impl Beep {
+    pub fn new(count: u8) -> Beep {
+        Beep {
+            pattern: BeepPattern::default(),
+            count,
+        }
+    }
+
+    pub fn with_pattern(mut self, pattern: BeepPattern) -> Beep {
+        self.pattern = pattern;
+        self
+    }
+}
+
+impl IntoEffects for Beep {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::Beep(self))
+    }
+}
@@ -0,0 +1,143 @@
+use std::iter::once;
+
+use crate::state::effect::{
+    Effect, IntoEffects,
+    image::{Dither, Image, Scaling},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuleStyle {
+    #[default]
+    Solid,
+    /// Dashes four times as long as the line is thick, or 8 dots, with gaps half as long.
+    Dashed,
+    /// Two lines of the thickness, as far apart as each is thick.
+    Double,
+}
+
+/// A horizontal line, sized in dots rather than characters.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub thickness_dots: u8,
+    pub style: RuleStyle,
+    /// Length in dots, or `None` for the whole printable width. Longer rules are cut to it.
+    pub width: Option<usize>,
+}
+
+impl Rule {
+    pub fn new() -> Rule {
+        Rule {
+            thickness_dots: 2,
+            style: RuleStyle::default(),
+            width: None,
+        }
+    }
+
+    pub fn with_thickness_dots(mut self, thickness_dots: u8) -> Rule {
+        self.thickness_dots = thickness_dots;
+        self
+    }
+
+    pub fn with_style(mut self, style: RuleStyle) -> Rule {
+        self.style = style;
+        self
+    }
+
+    pub fn with_width(mut self, width: usize) -> Rule {
+        self.width = Some(width);
+        self
+    }
+
+    /// Height of the rule in dots, including the gap of a double one.
+    pub fn height(&self) -> usize {
+        let thickness = self.thickness_dots as usize;
+        match self.style {
+            RuleStyle::Solid | RuleStyle::Dashed => thickness,
+            RuleStyle::Double => thickness * 3,
+        }
+    }
+
+    /// The rule as an image for a `printable_width` dots wide print area.
+    pub fn to_image(&self, printable_width: usize) -> Image {
+        let width = self.width.unwrap_or(printable_width).min(printable_width);
+        let thickness = self.thickness_dots as usize;
+        let dash = (thickness * 4).max(8);
+
+        let luma = (0..width * self.height())
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let printed = match self.style {
+                    RuleStyle::Solid => true,
+                    RuleStyle::Dashed => x % (dash + dash / 2) < dash,
+                    RuleStyle::Double => y < thickness || y >= thickness * 2,
+                };
+                if printed { 0 } else { 255 }
+            })
+            .collect();
+
+        Image::from_grayscale(width, self.height(), luma)
+            .with_dither(Dither::Threshold)
+            .with_scaling(Scaling::Original)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoEffects for Rule {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::Rule(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each row of the rule as `#` for a printed dot and `.` for a blank one.
+    fn rows(rule: &Rule, printable_width: usize) -> Vec<String> {
+        let bitmap = rule.to_image(printable_width).to_bitmap(printable_width);
+        (0..bitmap.height())
+            .map(|y| {
+                (0..bitmap.width())
+                    .map(|x| if bitmap.dot(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solid_rule_spans_the_print_area() {
+        assert_eq!(rows(&Rule::new(), 512), ["#".repeat(512), "#".repeat(512)]);
+    }
+
+    #[test]
+    fn width_is_cut_to_the_print_area() {
+        assert_eq!(rows(&Rule::new().with_width(20), 512)[0].len(), 20);
+        assert_eq!(rows(&Rule::new().with_width(1000), 512)[0].len(), 512);
+    }
+
+    #[test]
+    fn dashed_rule() {
+        let rule = Rule::new()
+            .with_thickness_dots(1)
+            .with_style(RuleStyle::Dashed)
+            .with_width(30);
+
+        assert_eq!(rows(&rule, 512), ["########....########....######"]);
+    }
+
+    #[test]
+    fn double_rule() {
+        let rule = Rule::new()
+            .with_thickness_dots(1)
+            .with_style(RuleStyle::Double)
+            .with_width(4);
+
+        assert_eq!(rule.height(), 3);
+        assert_eq!(rows(&rule, 512), ["####", "....", "####"]);
+    }
+}
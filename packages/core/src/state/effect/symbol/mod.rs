@@ -0,0 +1,219 @@
+use std::iter::once;
+
+use crate::{
+    state::effect::{
+        Effect, IntoEffects,
+        image::{Dither, Image, Scaling},
+    },
+    types::{
+        justification::Justification,
+        symbol::{DataMatrixSymbolType, Pdf417ErrorCorrection, QrErrorCorrection},
+    },
+};
+
+pub mod qr;
+
+/// When a symbol is rendered in software and printed as an image instead of by the printer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fallback {
+    /// Always use the printer's own encoder.
+    #[default]
+    Never,
+    /// Render the symbol if the model doesn't support the symbol commands.
+    WhenUnsupported,
+    /// Always render the symbol, e.g. for modes the model doesn't print symbols right in.
+    Always,
+}
+
+/// A QR Code Model 2.
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    pub data: Vec<u8>,
+    /// Width and height of a module in dots, 1–16.
+    pub module_size: u8,
+    pub error_correction: QrErrorCorrection,
+    pub justification: Justification,
+    pub fallback: Fallback,
+}
+
+impl QrCode {
+    pub fn new(data: impl AsRef<[u8]>) -> QrCode {
+        QrCode {
+            data: data.as_ref().to_vec(),
+            module_size: 3,
+            error_correction: QrErrorCorrection::default(),
+            justification: Justification::Centered,
+            fallback: Fallback::default(),
+        }
+    }
+
+    pub fn with_module_size(mut self, module_size: u8) -> QrCode {
+        self.module_size = module_size;
+        self
+    }
+
+    pub fn with_error_correction(mut self, error_correction: QrErrorCorrection) -> QrCode {
+        self.error_correction = error_correction;
+        self
+    }
+
+    pub fn with_justification(mut self, justification: Justification) -> QrCode {
+        self.justification = justification;
+        self
+    }
+
+    pub fn with_fallback(mut self, fallback: Fallback) -> QrCode {
+        self.fallback = fallback;
+        self
+    }
+
+    /// The symbol rendered in software, at the module size or as much of it as fits in
+    /// `printable_width` dots. Like the printer's, it has no quiet zone of its own.
+    ///
+    /// Data is encoded in byte mode, so `None` if it's longer than the 2953 bytes version 40
+    /// holds at level L, fewer at the other levels, or if the symbol doesn't fit at all.
+    pub fn to_image(&self, printable_width: usize) -> Option<Image> {
+        let matrix = qr::encode(&self.data, self.error_correction)?;
+        let size = matrix.size();
+        let module = (self.module_size as usize).min(printable_width / size);
+        if module == 0 {
+            return None;
+        }
+
+        let width = size * module;
+        let luma = (0..width * width)
+            .map(|i| {
+                let (x, y) = (i % width / module, i / width / module);
+                if matrix.dark(x, y) { 0 } else { 255 }
+            })
+            .collect();
+
+        Some(
+            Image::from_grayscale(width, width, luma)
+                .with_dither(Dither::Threshold)
+                .with_scaling(Scaling::Original),
+        )
+    }
+}
+
+impl IntoEffects for QrCode {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::QrCode(self))
+    }
+}
+
+/// A PDF417 symbol. There's no software fallback for it.
+#[derive(Debug, Clone)]
+pub struct Pdf417 {
+    pub data: Vec<u8>,
+    /// Columns in the data region, 1–30, or 0 to fit the data.
+    pub columns: u8,
+    /// Rows, 3–90, or 0 to fit the data.
+    pub rows: u8,
+    /// Width of a module in dots, 2–8.
+    pub module_width: u8,
+    /// Height of a row in multiples of the module width, 2–8.
+    pub row_height: u8,
+    pub error_correction: Pdf417ErrorCorrection,
+    /// Leave out the right row indicators and shorten the stop pattern.
+    pub truncated: bool,
+    pub justification: Justification,
+}
+
+impl Pdf417 {
+    pub fn new(data: impl AsRef<[u8]>) -> Pdf417 {
+        Pdf417 {
+            data: data.as_ref().to_vec(),
+            columns: 0,
+            rows: 0,
+            module_width: 3,
+            row_height: 3,
+            error_correction: Pdf417ErrorCorrection::default(),
+            truncated: false,
+            justification: Justification::Centered,
+        }
+    }
+
+    pub fn with_columns(mut self, columns: u8) -> Pdf417 {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_rows(mut self, rows: u8) -> Pdf417 {
+        self.rows = rows;
+        self
+    }
+
+    pub fn with_module_width(mut self, module_width: u8) -> Pdf417 {
+        self.module_width = module_width;
+        self
+    }
+
+    pub fn with_row_height(mut self, row_height: u8) -> Pdf417 {
+        self.row_height = row_height;
+        self
+    }
+
+    pub fn with_error_correction(mut self, error_correction: Pdf417ErrorCorrection) -> Pdf417 {
+        self.error_correction = error_correction;
+        self
+    }
+
+    pub fn with_truncated(mut self, truncated: bool) -> Pdf417 {
+        self.truncated = truncated;
+        self
+    }
+
+    pub fn with_justification(mut self, justification: Justification) -> Pdf417 {
+        self.justification = justification;
+        self
+    }
+}
+
+impl IntoEffects for Pdf417 {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::Pdf417(self))
+    }
+}
+
+/// A DataMatrix ECC 200 symbol. There's no software fallback for it.
+#[derive(Debug, Clone)]
+pub struct DataMatrix {
+    pub data: Vec<u8>,
+    pub symbol_type: DataMatrixSymbolType,
+    /// Width and height of a module in dots, 2–16.
+    pub module_size: u8,
+    pub justification: Justification,
+}
+
+impl DataMatrix {
+    pub fn new(data: impl AsRef<[u8]>) -> DataMatrix {
+        DataMatrix {
+            data: data.as_ref().to_vec(),
+            symbol_type: DataMatrixSymbolType::default(),
+            module_size: 3,
+            justification: Justification::Centered,
+        }
+    }
+
+    pub fn with_symbol_type(mut self, symbol_type: DataMatrixSymbolType) -> DataMatrix {
+        self.symbol_type = symbol_type;
+        self
+    }
+
+    pub fn with_module_size(mut self, module_size: u8) -> DataMatrix {
+        self.module_size = module_size;
+        self
+    }
+
+    pub fn with_justification(mut self, justification: Justification) -> DataMatrix {
+        self.justification = justification;
+        self
+    }
+}
+
+impl IntoEffects for DataMatrix {
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        once(Effect::DataMatrix(self))
+    }
+}
@@ -0,0 +1,486 @@
+//! A QR Code Model 2 encoder for printing symbols as images. Data is always encoded in byte
+//! mode, in the smallest version that holds it; the mask is picked by the usual penalty rules.
+
+use crate::types::symbol::QrErrorCorrection;
+
+/// Error correction codewords per block, by level (L, M, Q, H) and version.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Error correction blocks, by level (L, M, Q, H) and version.
+const ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// A QR Code symbol without its quiet zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    size: usize,
+    /// Row-major, `true` for a dark module.
+    modules: Vec<bool>,
+    /// Finder, timing, alignment, format and version modules, which data and masks skip.
+    function: Vec<bool>,
+}
+
+impl Matrix {
+    /// Modules per side.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+}
+
+fn level_index(level: QrErrorCorrection) -> usize {
+    match level {
+        QrErrorCorrection::L => 0,
+        QrErrorCorrection::M => 1,
+        QrErrorCorrection::Q => 2,
+        QrErrorCorrection::H => 3,
+    }
+}
+
+/// The level's bits in the format information, which aren't in L, M, Q, H order.
+fn format_bits(level: QrErrorCorrection) -> u32 {
+    match level {
+        QrErrorCorrection::L => 1,
+        QrErrorCorrection::M => 0,
+        QrErrorCorrection::Q => 3,
+        QrErrorCorrection::H => 2,
+    }
+}
+
+/// Modules left for data and error correction once the function patterns are placed.
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn data_codewords(version: usize, level: QrErrorCorrection) -> usize {
+    let l = level_index(level);
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[l][version] as usize
+            * ERROR_CORRECTION_BLOCKS[l][version] as usize
+}
+
+/// Centres of the alignment patterns along either axis.
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return vec![];
+    }
+
+    let count = version / 7 + 2;
+    let step = if version == 32 {
+        26
+    } else {
+        (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2
+    };
+
+    let mut positions: Vec<usize> = (0..count - 1)
+        .map(|i| version * 4 + 10 - i * step)
+        .collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Multiplication in GF(2⁸) modulo x⁸ + x⁴ + x³ + x² + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ if z & 0x80 != 0 { 0x1d } else { 0 };
+        if (y >> i) & 1 != 0 {
+            z ^= x;
+        }
+    }
+    z
+}
+
+/// The Reed–Solomon generator polynomial of `degree`, highest coefficient first and the
+/// leading 1 left out.
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+/// The error correction codewords for `data`.
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(d, factor);
+        }
+    }
+    result
+}
+
+/// The data codewords: mode, count, data, terminator and padding.
+fn data_bits(data: &[u8], version: usize, capacity: usize) -> Vec<u8> {
+    let mut bits: Vec<bool> = vec![];
+    let mut push = |value: u32, len: usize| {
+        bits.extend((0..len).rev().map(|i| (value >> i) & 1 != 0));
+    };
+
+    // Byte mode, with a 16 bit count from version 10
+    push(0b0100, 4);
+    push(data.len() as u32, if version < 10 { 8 } else { 16 });
+    for &b in data {
+        push(b.into(), 8);
+    }
+
+    let capacity_bits = capacity * 8;
+    let terminator = (capacity_bits - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    bits.extend(std::iter::repeat_n(
+        false,
+        bits.len().next_multiple_of(8) - bits.len(),
+    ));
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+        .collect();
+    codewords.extend(
+        [0xec, 0x11]
+            .into_iter()
+            .cycle()
+            .take(capacity - codewords.len()),
+    );
+    codewords
+}
+
+/// The data split into blocks, each with its error correction, interleaved.
+fn interleaved(data: &[u8], version: usize, level: QrErrorCorrection) -> Vec<u8> {
+    let l = level_index(level);
+    let blocks = ERROR_CORRECTION_BLOCKS[l][version] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[l][version] as usize;
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+
+    let divisor = rs_divisor(ecc_len);
+    let mut split = vec![];
+    let mut start = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let block = &data[start..start + len];
+        start += len;
+
+        let ecc = rs_remainder(block, &divisor);
+        // Short blocks get a placeholder so that all line up when interleaving
+        let mut full = block.to_vec();
+        if i < short_blocks {
+            full.push(0);
+        }
+        full.extend(ecc);
+        split.push(full);
+    }
+
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..=short_len {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+fn draw_function_patterns(matrix: &mut Matrix, version: usize) {
+    let size = matrix.size;
+
+    for i in 0..size {
+        matrix.set_function(6, i, i % 2 == 0);
+        matrix.set_function(i, 6, i % 2 == 0);
+    }
+
+    // Finders, with their separators
+    for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+        for dy in -4isize..=4 {
+            for dx in -4isize..=4 {
+                let (x, y) = (cx.wrapping_add_signed(dx), cy.wrapping_add_signed(dy));
+                if x < size && y < size {
+                    let distance = dx.abs().max(dy.abs());
+                    matrix.set_function(x, y, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    let positions = alignment_positions(version);
+    let last = positions.len().saturating_sub(1);
+    for (i, &cx) in positions.iter().enumerate() {
+        for (j, &cy) in positions.iter().enumerate() {
+            // Skip the three that would overlap the finders
+            if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                continue;
+            }
+            for dy in -2isize..=2 {
+                for dx in -2isize..=2 {
+                    let (x, y) = (cx.wrapping_add_signed(dx), cy.wrapping_add_signed(dy));
+                    matrix.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+                }
+            }
+        }
+    }
+
+    // Placeholders, so that the codewords skip them
+    draw_format_bits(matrix, QrErrorCorrection::M, 0);
+
+    if version >= 7 {
+        let mut remainder = version as u32;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+        }
+        let bits = (version as u32) << 12 | remainder;
+
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let (a, b) = (size - 11 + i % 3, i / 3);
+            matrix.set_function(a, b, dark);
+            matrix.set_function(b, a, dark);
+        }
+    }
+}
+
+fn draw_format_bits(matrix: &mut Matrix, level: QrErrorCorrection, mask: u32) {
+    let size = matrix.size;
+    let data = format_bits(level) << 3 | mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    let bits = (data << 10 | remainder) ^ 0x5412;
+    let bit = |i: usize| (bits >> i) & 1 != 0;
+
+    // Around the top left finder
+    for i in 0..=5 {
+        matrix.set_function(8, i, bit(i));
+    }
+    matrix.set_function(8, 7, bit(6));
+    matrix.set_function(8, 8, bit(7));
+    matrix.set_function(7, 8, bit(8));
+    for i in 9..15 {
+        matrix.set_function(14 - i, 8, bit(i));
+    }
+
+    // Split between the other two
+    for i in 0..8 {
+        matrix.set_function(size - 1 - i, 8, bit(i));
+    }
+    for i in 8..15 {
+        matrix.set_function(8, size - 15 + i, bit(i));
+    }
+    matrix.set_function(8, size - 8, true);
+}
+
+/// Places the codewords in the zigzag of two module wide columns, right to left.
+fn draw_codewords(matrix: &mut Matrix, codewords: &[u8]) {
+    let size = matrix.size;
+    let mut i = 0;
+
+    let mut right = size - 1;
+    while right >= 1 {
+        // The vertical timing pattern takes a whole column
+        if right == 6 {
+            right = 5;
+        }
+        let upward = (right + 1) & 2 == 0;
+        for vertical in 0..size {
+            let y = if upward {
+                size - 1 - vertical
+            } else {
+                vertical
+            };
+            for x in [right, right - 1] {
+                if !matrix.function[y * size + x] && i < codewords.len() * 8 {
+                    matrix.modules[y * size + x] = (codewords[i / 8] >> (7 - i % 8)) & 1 != 0;
+                    i += 1;
+                }
+            }
+        }
+        if right < 2 {
+            break;
+        }
+        right -= 2;
+    }
+}
+
+/// Flips the data modules `mask` selects. Applying the same mask twice undoes it.
+fn apply_mask(matrix: &mut Matrix, mask: u32) {
+    let size = matrix.size;
+    for y in 0..size {
+        for x in 0..size {
+            let flip = match mask {
+                0 => (x + y) % 2 == 0,
+                1 => y % 2 == 0,
+                2 => x % 3 == 0,
+                3 => (x + y) % 3 == 0,
+                4 => (x / 3 + y / 2) % 2 == 0,
+                5 => x * y % 2 + x * y % 3 == 0,
+                6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+            };
+            if flip && !matrix.function[y * size + x] {
+                matrix.modules[y * size + x] ^= true;
+            }
+        }
+    }
+}
+
+/// The penalty for `matrix`'s runs, blocks, finder lookalikes and dark/light imbalance; the
+/// mask with the lowest wins.
+fn penalty(matrix: &Matrix) -> usize {
+    let size = matrix.size;
+    let mut score = 0;
+
+    // Lines of modules, both ways, with light past the edges
+    let lines = (0..size).flat_map(|i| {
+        [
+            (0..size).map(|j| matrix.dark(j, i)).collect::<Vec<_>>(),
+            (0..size).map(|j| matrix.dark(i, j)).collect(),
+        ]
+    });
+    for line in lines {
+        let mut run = 1;
+        for j in 1..=size {
+            if j < size && line[j] == line[j - 1] {
+                run += 1;
+                continue;
+            }
+            if run >= 5 {
+                score += 3 + run - 5;
+            }
+            run = 1;
+        }
+
+        let padded: Vec<bool> = [false; 4]
+            .into_iter()
+            .chain(line.iter().copied())
+            .chain([false; 4])
+            .collect();
+        let finder = [true, false, true, true, true, false, true];
+        for window in padded.windows(11) {
+            let before = window[..4].iter().all(|d| !d) && window[4..] == finder;
+            let after = window[..7] == finder && window[7..].iter().all(|d| !d);
+            if before || after {
+                score += 40;
+            }
+        }
+    }
+
+    for y in 0..size - 1 {
+        for x in 0..size - 1 {
+            let dark = matrix.dark(x, y);
+            if dark == matrix.dark(x + 1, y)
+                && dark == matrix.dark(x, y + 1)
+                && dark == matrix.dark(x + 1, y + 1)
+            {
+                score += 3;
+            }
+        }
+    }
+
+    let total = size * size;
+    let dark = matrix.modules.iter().filter(|d| **d).count();
+    // Each 5% away from half dark costs 10
+    let k = (dark * 20)
+        .abs_diff(total * 10)
+        .div_ceil(total)
+        .saturating_sub(1);
+    score + k * 10
+}
+
+/// `data` as a QR Code, or `None` if it doesn't fit in version 40 at `level`.
+pub fn encode(data: &[u8], level: QrErrorCorrection) -> Option<Matrix> {
+    let version = (1..=40).find(|&version| {
+        let count_bits = if version < 10 { 8 } else { 16 };
+        data.len() < 1 << count_bits
+            && 4 + count_bits + data.len() * 8 <= data_codewords(version, level) * 8
+    })?;
+
+    let codewords = data_bits(data, version, data_codewords(version, level));
+    let codewords = interleaved(&codewords, version, level);
+
+    let size = version * 4 + 17;
+    let mut matrix = Matrix {
+        size,
+        modules: vec![false; size * size],
+        function: vec![false; size * size],
+    };
+    draw_function_patterns(&mut matrix, version);
+    draw_codewords(&mut matrix, &codewords);
+
+    let mut best = (usize::MAX, 0);
+    for mask in 0..8 {
+        apply_mask(&mut matrix, mask);
+        draw_format_bits(&mut matrix, level, mask);
+        best = best.min((penalty(&matrix), mask));
+        apply_mask(&mut matrix, mask);
+    }
+
+    let mask = best.1;
+    apply_mask(&mut matrix, mask);
+    draw_format_bits(&mut matrix, level, mask);
+    Some(matrix)
+}
@@ -2,27 +2,62 @@ pub mod delta;
 pub mod effect;
 
 use crate::{
-    state::delta::Delta,
+    commands::reader::state::Mode,
+    state::{
+        delta::Delta,
+        effect::{Effect, IntoEffects},
+    },
     types::{
         character_set::{AsciiVariant, Codepage},
         font::Font,
         justification::Justification,
+        print_color::PrintColor,
     },
 };
 
-#[derive(Clone, Default)]
+/// Line spacing, as set by `ESC 2` or `ESC 3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineSpacing {
+    /// The printer's default, about 1/6 inch.
+    Default,
+    /// `n` × the vertical or horizontal motion unit.
+    Units(u8),
+}
+
+/// The printer's print modes. `None` for modes that aren't known or don't matter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct State {
     pub(crate) font: Option<Font>,
     pub(crate) ascii_variant: Option<AsciiVariant>,
     pub(crate) codepage: Option<Codepage>,
     pub(crate) justification: Option<Justification>,
     pub(crate) text_scale: Option<(u8, u8)>,
+    pub(crate) emphasized: Option<bool>,
+    /// `ESC -`: 0 off, 1 one dot thick, 2 two dots thick.
+    pub(crate) underline: Option<u8>,
+    pub(crate) double_strike: Option<bool>,
+    pub(crate) reverse: Option<bool>,
+    pub(crate) upside_down: Option<bool>,
+    /// `ESC V`: 0 off, 1 with 1-dot character spacing, 2 with 1.5-dot character spacing.
+    pub(crate) rotation: Option<u8>,
+    pub(crate) line_spacing: Option<LineSpacing>,
+    pub(crate) right_spacing: Option<u8>,
+    pub(crate) print_color: Option<PrintColor>,
+    pub(crate) left_margin: Option<u16>,
+    pub(crate) print_area_width: Option<u16>,
+    pub(crate) user_defined_characters: Option<bool>,
+    pub(crate) mode: Option<Mode>,
 }
 
 pub trait IntoState {
     fn into_state(&self) -> State;
 }
 
+/// `into` if it's set and differs from `from`.
+fn changed<T: PartialEq + Copy>(from: Option<T>, into: Option<T>) -> Option<T> {
+    into.filter(|v| Some(*v) != from)
+}
+
 impl State {
     pub fn with_codepage(mut self, codepage: Codepage) -> Self {
         self.codepage = Some(codepage);
@@ -49,6 +84,71 @@ impl State {
         self
     }
 
+    pub fn with_emphasized(mut self, emphasized: bool) -> Self {
+        self.emphasized = Some(emphasized);
+        self
+    }
+
+    pub fn with_underline(mut self, underline: u8) -> Self {
+        self.underline = Some(underline);
+        self
+    }
+
+    pub fn with_double_strike(mut self, double_strike: bool) -> Self {
+        self.double_strike = Some(double_strike);
+        self
+    }
+
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = Some(reverse);
+        self
+    }
+
+    pub fn with_upside_down(mut self, upside_down: bool) -> Self {
+        self.upside_down = Some(upside_down);
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: u8) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    pub fn with_line_spacing(mut self, line_spacing: LineSpacing) -> Self {
+        self.line_spacing = Some(line_spacing);
+        self
+    }
+
+    pub fn with_right_spacing(mut self, right_spacing: u8) -> Self {
+        self.right_spacing = Some(right_spacing);
+        self
+    }
+
+    pub fn with_print_color(mut self, print_color: PrintColor) -> Self {
+        self.print_color = Some(print_color);
+        self
+    }
+
+    pub fn with_left_margin(mut self, left_margin: u16) -> Self {
+        self.left_margin = Some(left_margin);
+        self
+    }
+
+    pub fn with_print_area_width(mut self, print_area_width: u16) -> Self {
+        self.print_area_width = Some(print_area_width);
+        self
+    }
+
+    pub fn with_user_defined_characters(mut self, enabled: bool) -> Self {
+        self.user_defined_characters = Some(enabled);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
     pub fn codepage(&self) -> Option<Codepage> {
         self.codepage
     }
@@ -69,39 +169,179 @@ impl State {
         self.text_scale
     }
 
-    pub fn delta(&self, into: State) -> Delta {
-        let mut delta = Delta::empty();
+    pub fn emphasized(&self) -> Option<bool> {
+        self.emphasized
+    }
 
-        if let Some(font) = into.font
-            && Some(font) != self.font
-        {
-            delta.apply_font = Some(font);
-        }
+    pub fn underline(&self) -> Option<u8> {
+        self.underline
+    }
 
-        if let Some(ascii_variant) = into.ascii_variant
-            && Some(ascii_variant) != self.ascii_variant
-        {
-            delta.apply_ascii_variant = Some(ascii_variant);
-        }
+    pub fn double_strike(&self) -> Option<bool> {
+        self.double_strike
+    }
 
-        if let Some(codepage) = into.codepage
-            && Some(codepage) != self.codepage
-        {
-            delta.apply_codepage = Some(codepage);
-        }
+    pub fn reverse(&self) -> Option<bool> {
+        self.reverse
+    }
+
+    pub fn upside_down(&self) -> Option<bool> {
+        self.upside_down
+    }
+
+    pub fn rotation(&self) -> Option<u8> {
+        self.rotation
+    }
+
+    pub fn line_spacing(&self) -> Option<LineSpacing> {
+        self.line_spacing
+    }
+
+    pub fn right_spacing(&self) -> Option<u8> {
+        self.right_spacing
+    }
+
+    pub fn print_color(&self) -> Option<PrintColor> {
+        self.print_color
+    }
+
+    pub fn left_margin(&self) -> Option<u16> {
+        self.left_margin
+    }
+
+    pub fn print_area_width(&self) -> Option<u16> {
+        self.print_area_width
+    }
+
+    pub fn user_defined_characters(&self) -> Option<bool> {
+        self.user_defined_characters
+    }
+
+    pub fn mode(&self) -> Option<Mode> {
+        self.mode
+    }
 
-        if let Some(justification) = into.justification
-            && Some(justification) != self.justification
-        {
-            delta.apply_justification = Some(justification)
+    /// Whether `self` leaves every mode as it is.
+    pub fn is_empty(&self) -> bool {
+        *self == State::default()
+    }
+
+    /// The modes `into` sets to something other than `self`.
+    pub fn changes(&self, into: &State) -> State {
+        State {
+            font: changed(self.font, into.font),
+            ascii_variant: changed(self.ascii_variant, into.ascii_variant),
+            codepage: changed(self.codepage, into.codepage),
+            justification: changed(self.justification, into.justification),
+            text_scale: changed(self.text_scale, into.text_scale),
+            emphasized: changed(self.emphasized, into.emphasized),
+            underline: changed(self.underline, into.underline),
+            double_strike: changed(self.double_strike, into.double_strike),
+            reverse: changed(self.reverse, into.reverse),
+            upside_down: changed(self.upside_down, into.upside_down),
+            rotation: changed(self.rotation, into.rotation),
+            line_spacing: changed(self.line_spacing, into.line_spacing),
+            right_spacing: changed(self.right_spacing, into.right_spacing),
+            print_color: changed(self.print_color, into.print_color),
+            left_margin: changed(self.left_margin, into.left_margin),
+            print_area_width: changed(self.print_area_width, into.print_area_width),
+            user_defined_characters: changed(
+                self.user_defined_characters,
+                into.user_defined_characters,
+            ),
+            mode: changed(self.mode, into.mode),
         }
+    }
 
-        if let Some(scale) = into.text_scale
-            && Some(scale) != self.text_scale
-        {
-            delta.apply_text_scale = Some(scale)
+    /// `self` with every mode `other` sets replaced by `other`'s.
+    pub fn merged_with(self, other: State) -> State {
+        State {
+            font: other.font.or(self.font),
+            ascii_variant: other.ascii_variant.or(self.ascii_variant),
+            codepage: other.codepage.or(self.codepage),
+            justification: other.justification.or(self.justification),
+            text_scale: other.text_scale.or(self.text_scale),
+            emphasized: other.emphasized.or(self.emphasized),
+            underline: other.underline.or(self.underline),
+            double_strike: other.double_strike.or(self.double_strike),
+            reverse: other.reverse.or(self.reverse),
+            upside_down: other.upside_down.or(self.upside_down),
+            rotation: other.rotation.or(self.rotation),
+            line_spacing: other.line_spacing.or(self.line_spacing),
+            right_spacing: other.right_spacing.or(self.right_spacing),
+            print_color: other.print_color.or(self.print_color),
+            left_margin: other.left_margin.or(self.left_margin),
+            print_area_width: other.print_area_width.or(self.print_area_width),
+            user_defined_characters: other
+                .user_defined_characters
+                .or(self.user_defined_characters),
+            mode: other.mode.or(self.mode),
         }
+    }
+
+    /// The changes that take `self` to `into`, as a delta holding a single state transition, or
+    /// nothing if `into` changes no mode.
+    pub fn delta(&self, into: State) -> Delta {
+        Delta::empty().with(self.changes(&into))
+    }
+}
+
+impl IntoEffects for State {
+    /// A transition to `self`, or nothing if it leaves every mode as it is.
+    fn as_effects(self) -> impl Iterator<Item = Effect> {
+        (!self.is_empty())
+            .then_some(Effect::SetState(self))
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_with_keeps_what_other_leaves_unset() {
+        let state = State::default()
+            .with_font(Font::A)
+            .with_emphasized(true)
+            .merged_with(
+                State::default()
+                    .with_emphasized(false)
+                    .with_justification(Justification::Centered),
+            );
+
+        assert_eq!(
+            state,
+            State::default()
+                .with_font(Font::A)
+                .with_emphasized(false)
+                .with_justification(Justification::Centered)
+        );
+    }
+
+    #[test]
+    fn delta_only_holds_changes() {
+        let from = State::default().with_font(Font::A).with_underline(1);
+        let into = State::default()
+            .with_font(Font::A)
+            .with_underline(2)
+            .with_reverse(true);
+
+        let effects: Vec<Effect> = from.delta(into).iter().cloned().collect();
+        let [Effect::SetState(changes)] = &effects[..] else {
+            panic!("expected a single transition, got {effects:?}");
+        };
+        assert_eq!(
+            *changes,
+            State::default().with_underline(2).with_reverse(true)
+        );
+    }
+
+    #[test]
+    fn delta_to_the_same_state_is_empty() {
+        let state = State::default().with_font(Font::B).with_text_scale((2, 2));
 
-        delta
+        assert_eq!(state.delta(state.clone()).iter().count(), 0);
+        assert_eq!(state.delta(State::default()).iter().count(), 0);
     }
 }
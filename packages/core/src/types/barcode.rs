@@ -0,0 +1,127 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    error::{ContextError, ErrMode},
+    token::{take, take_till},
+};
+
+use crate::commands::reader::error::ErrorCtx;
+
+/// `GS H`: where the human readable interpretation is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum HriPosition {
+    NotPrinted = 0,
+    Above = 1,
+    Below = 2,
+    Both = 3,
+}
+
+impl HriPosition {
+    /// Accepts both `0`–`3` and `'0'`–`'3'`.
+    pub fn from_bits(bits: u8) -> Option<HriPosition> {
+        Some(match bits {
+            0 | b'0' => HriPosition::NotPrinted,
+            1 | b'1' => HriPosition::Above,
+            2 | b'2' => HriPosition::Below,
+            3 | b'3' => HriPosition::Both,
+
+            _ => return None,
+        })
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Self::from_bits)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
+
+/// `GS k` barcode systems, by their `m` in the length-prefixed form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum BarcodeSystem {
+    UpcA = 65,
+    UpcE = 66,
+    /// EAN-13, also JAN-13.
+    Ean13 = 67,
+    /// EAN-8, also JAN-8.
+    Ean8 = 68,
+    Code39 = 69,
+    Itf = 70,
+    /// Codabar, also NW-7.
+    Codabar = 71,
+    Code93 = 72,
+    Code128 = 73,
+    Gs1_128 = 74,
+    Gs1DataBarOmnidirectional = 75,
+    Gs1DataBarTruncated = 76,
+    Gs1DataBarLimited = 77,
+    Gs1DataBarExpanded = 78,
+}
+
+impl BarcodeSystem {
+    /// Accepts `m` from either form: `0`–`6` for the NUL-terminated one, `65`–`78` for the
+    /// length-prefixed one.
+    pub fn from_bits(bits: u8) -> Option<BarcodeSystem> {
+        Some(match bits {
+            0 | 65 => BarcodeSystem::UpcA,
+            1 | 66 => BarcodeSystem::UpcE,
+            2 | 67 => BarcodeSystem::Ean13,
+            3 | 68 => BarcodeSystem::Ean8,
+            4 | 69 => BarcodeSystem::Code39,
+            5 | 70 => BarcodeSystem::Itf,
+            6 | 71 => BarcodeSystem::Codabar,
+            72 => BarcodeSystem::Code93,
+            73 => BarcodeSystem::Code128,
+            74 => BarcodeSystem::Gs1_128,
+            75 => BarcodeSystem::Gs1DataBarOmnidirectional,
+            76 => BarcodeSystem::Gs1DataBarTruncated,
+            77 => BarcodeSystem::Gs1DataBarLimited,
+            78 => BarcodeSystem::Gs1DataBarExpanded,
+
+            _ => return None,
+        })
+    }
+}
+
+/// `GS k`: a barcode system and its data, as the printer takes it.
+///
+/// Both forms parse; the length-prefixed one is written, so data is limited to 255 bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct BarcodeData {
+    pub system: BarcodeSystem,
+    pub data: Vec<u8>,
+}
+
+impl BarcodeData {
+    pub fn new(system: BarcodeSystem, data: Vec<u8>) -> Self {
+        Self { system, data }
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        move |input: &mut Partial<&'i [u8]>| {
+            let (m, system) = u8
+                .verify_map(|m| BarcodeSystem::from_bits(m).map(|system| (m, system)))
+                .parse_next(input)?;
+
+            let data: &[u8] = if m <= 6 {
+                let data = take_till(0.., 0).parse_next(input)?;
+                u8.parse_next(input)?;
+                data
+            } else {
+                let n = u8.parse_next(input)?;
+                take(n).parse_next(input)?
+            };
+
+            Ok(Self::new(system, data.to_vec()))
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&[self.system as u8, self.data.len() as u8][..], &self.data].concat()
+    }
+}
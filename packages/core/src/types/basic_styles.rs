@@ -11,6 +11,7 @@ use winnow::{
 use crate::commands::reader::error::ErrorCtx;
 
 #[bitfield(u8)]
+#[derive(PartialEq, Eq)]
 pub struct BasicStyles {
     #[bits(1)]
     pub font_index: u8,
@@ -69,4 +70,8 @@ impl BasicStyles {
     pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
         u8.map(|v| BasicStyles::from_bits(v))
     }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [self.into_bits()]
+    }
 }
@@ -1,15 +1,37 @@
 use facet::Facet;
 
-#[derive(Clone, Copy, Facet)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Facet)]
 #[repr(C)]
 pub enum BatchPrintMode {
     Disable,
     Enable,
 }
 
-#[derive(Clone, Copy, Facet)]
+impl BatchPrintMode {
+    pub fn from_bits(bits: u8) -> Option<BatchPrintMode> {
+        Some(match bits {
+            0 | b'0' => BatchPrintMode::Disable,
+            1 | b'1' => BatchPrintMode::Enable,
+
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Facet)]
 #[repr(C)]
 pub enum BatchPrintDirection {
     Forward,
     Reverse,
 }
+
+impl BatchPrintDirection {
+    pub fn from_bits(bits: u8) -> Option<BatchPrintDirection> {
+        Some(match bits {
+            0 | b'0' => BatchPrintDirection::Forward,
+            1 | b'1' => BatchPrintDirection::Reverse,
+
+            _ => return None,
+        })
+    }
+}
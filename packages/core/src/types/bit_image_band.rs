@@ -33,6 +33,16 @@ impl BitImageMode {
         }
     }
 
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::Single8 => 0,
+            Self::Double8 => 1,
+            Self::Single24 => 32,
+            Self::Double24 => 33,
+            Self::Other(x) => x,
+        }
+    }
+
     pub fn bytes_per_col(self) -> usize {
         match self {
             Self::Single8 | Self::Double8 => 1,
@@ -52,7 +62,7 @@ impl BitImageMode {
     }
 }
 
-#[derive(Clone, Facet, Debug)]
+#[derive(Clone, Facet, Debug, PartialEq, Eq)]
 pub struct BitImageBand {
     mode: BitImageMode,
     width_cols: u16,
@@ -61,9 +71,72 @@ pub struct BitImageBand {
     canvas: Vec<u8>,
 }
 
+impl BitImageBand {
+    /// A band of `mode`'s height from a row-major canvas of 0/1 entries, `width_cols` per row.
+    pub fn new(mode: BitImageMode, width_cols: u16, canvas: Vec<u8>) -> Self {
+        Self {
+            mode,
+            width_cols,
+            height_rows: mode.height_rows(),
+            canvas,
+        }
+    }
+
+    pub fn mode(&self) -> BitImageMode {
+        self.mode
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width_cols
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height_rows
+    }
+
+    pub fn canvas(&self) -> &[u8] {
+        &self.canvas
+    }
+
+    /// The canvas packed back into the printer's column format.
+    pub fn to_data(&self) -> Vec<u8> {
+        let w = self.width_cols as usize;
+        let bpc = self.mode.bytes_per_col();
+        let mut data = vec![0u8; w * bpc];
+
+        for y in 0..(self.height_rows as usize).min(bpc * 8) {
+            for x in 0..w {
+                if self.canvas[y * w + x] != 0 {
+                    data[x * bpc + y / 8] |= 0x80 >> (y % 8);
+                }
+            }
+        }
+
+        data
+    }
+}
+
 impl<'i> BitImageBand {
     pub fn parser() -> impl Parser<Partial<&'i [u8]>, BitImageBand, ErrMode<ContextError<ErrorCtx>>>
     {
+        BitImageBandRef::parser().map(|band| band.into_owned())
+    }
+}
+
+/// A bit image band borrowed from the input, still in the printer's column format.
+///
+/// Dots are unpacked on demand; use [`BitImageBandRef::into_owned`] to get a full canvas.
+#[derive(Clone, Copy, Debug)]
+pub struct BitImageBandRef<'i> {
+    mode: BitImageMode,
+    width_cols: u16,
+    /// Column-major payload: bytes_per_col bytes per column, MSB on top
+    data: &'i [u8],
+}
+
+impl<'i> BitImageBandRef<'i> {
+    pub fn parser()
+    -> impl Parser<Partial<&'i [u8]>, BitImageBandRef<'i>, ErrMode<ContextError<ErrorCtx>>> {
         move |input: &mut Partial<&'i [u8]>| {
             // Mode and width bytes
             let m_byte = any.parse_next(input)?;
@@ -77,55 +150,66 @@ impl<'i> BitImageBand {
             // fail if:
             //   width_cols == 0
 
-            let bpc = mode.bytes_per_col();
-            let k = (width_cols as usize) * bpc;
+            let k = (width_cols as usize) * mode.bytes_per_col();
 
             // Payload bytes
             let data = take(k).parse_next(input)?;
 
-            let height_rows = mode.height_rows();
-            let w = width_cols as usize;
-            let h = height_rows as usize;
-            let mut canvas = vec![0u8; w * h];
-
-            match bpc {
-                1 => {
-                    for x in 0..w {
-                        let b = data[x];
-                        for bit in 0..8 {
-                            let on = (b >> (7 - bit)) & 1 == 1;
-                            if on {
-                                canvas[bit * w + x] = 1;
-                            }
-                        }
-                    }
-                }
-                3 => {
-                    for x in 0..w {
-                        let i = x * 3;
-                        let (b0, b1, b2) = (data[i], data[i + 1], data[i + 2]);
-                        for bit in 0..8 {
-                            if ((b0 >> (7 - bit)) & 1) == 1 {
-                                canvas[(0 + bit) * w + x] = 1;
-                            }
-                            if ((b1 >> (7 - bit)) & 1) == 1 {
-                                canvas[(8 + bit) * w + x] = 1;
-                            }
-                            if ((b2 >> (7 - bit)) & 1) == 1 {
-                                canvas[(16 + bit) * w + x] = 1;
-                            }
-                        }
-                    }
-                }
-                _ => unreachable!(),
-            }
-
-            Ok(BitImageBand {
+            Ok(BitImageBandRef {
                 mode,
                 width_cols,
-                height_rows,
-                canvas,
+                data,
             })
         }
     }
+
+    pub fn mode(&self) -> BitImageMode {
+        self.mode
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width_cols
+    }
+
+    pub fn height(&self) -> u16 {
+        self.mode.height_rows()
+    }
+
+    /// The raw column-format payload, as sent to the printer.
+    pub fn data(&self) -> &'i [u8] {
+        self.data
+    }
+
+    pub fn dot(&self, x: u16, y: u16) -> bool {
+        if x >= self.width_cols || y >= self.height() {
+            return false;
+        }
+
+        let bpc = self.mode.bytes_per_col();
+        let b = self.data[(x as usize) * bpc + (y as usize) / 8];
+
+        (b >> (7 - (y % 8))) & 1 == 1
+    }
+
+    pub fn into_owned(self) -> BitImageBand {
+        let height_rows = self.height();
+        let w = self.width_cols as usize;
+        let h = height_rows as usize;
+        let mut canvas = vec![0u8; w * h];
+
+        for y in 0..height_rows {
+            for x in 0..self.width_cols {
+                if self.dot(x, y) {
+                    canvas[(y as usize) * w + (x as usize)] = 1;
+                }
+            }
+        }
+
+        BitImageBand {
+            mode: self.mode,
+            width_cols: self.width_cols,
+            height_rows,
+            canvas,
+        }
+    }
 }
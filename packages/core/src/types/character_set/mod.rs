@@ -4,6 +4,13 @@ pub mod into_state;
 use facet::Facet;
 use strum::{Display, EnumIter, EnumString, FromRepr};
 use thermal_encoding::encoding::Encoding;
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    error::{ContextError, ErrMode},
+};
+
+use crate::commands::reader::error::ErrorCtx;
 
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, FromRepr, Facet)]
@@ -69,6 +76,16 @@ pub enum AsciiVariant {
     IndiaMarathi = 82,
 }
 
+impl AsciiVariant {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(AsciiVariant::from_repr)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, Display, FromRepr, Facet)]
 pub enum Codepage {
@@ -197,3 +214,13 @@ pub enum Codepage {
     #[strum(to_string = "Page 255 (User-defined / Space)")]
     Page255 = 255,
 }
+
+impl Codepage {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Codepage::from_repr)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
@@ -1,5 +1,13 @@
 use facet::Facet;
 use strum::{Display, EnumIter, EnumString, FromRepr};
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    combinator::{dispatch, empty, fail},
+    error::{ContextError, ErrMode},
+};
+
+use crate::commands::reader::error::ErrorCtx;
 
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, Display, FromRepr, Facet)]
@@ -16,3 +24,45 @@ pub enum CutMode {
     SetCuttingPosition(u8, CuttingShape),
     FeedAndCutAndMoveToStart(u8, CuttingShape),
 }
+
+impl CutMode {
+    /// Parse `m`, and `n` for the modes that feed.
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        use CuttingShape::{Full, Partial};
+
+        dispatch!(u8;
+            0x00 | b'0' => empty.value(CutMode::Cut(Full)),
+            0x01 | b'1' => empty.value(CutMode::Cut(Partial)),
+
+            b'A' => u8.map(|n| CutMode::FeedAndCut(n, Full)),
+            b'B' => u8.map(|n| CutMode::FeedAndCut(n, Partial)),
+
+            b'a' => u8.map(|n| CutMode::SetCuttingPosition(n, Full)),
+            b'b' => u8.map(|n| CutMode::SetCuttingPosition(n, Partial)),
+
+            b'g' => u8.map(|n| CutMode::FeedAndCutAndMoveToStart(n, Full)),
+            b'h' => u8.map(|n| CutMode::FeedAndCutAndMoveToStart(n, Partial)),
+
+            _ => fail,
+        )
+    }
+
+    /// The `m` byte that selects this mode.
+    pub const fn m(&self) -> u8 {
+        match *self {
+            CutMode::Cut(shape) => shape as u8,
+            CutMode::FeedAndCut(_, shape) => b'A' + shape as u8,
+            CutMode::SetCuttingPosition(_, shape) => b'a' + shape as u8,
+            CutMode::FeedAndCutAndMoveToStart(_, shape) => b'g' + shape as u8,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match *self {
+            CutMode::Cut(_) => vec![self.m()],
+            CutMode::FeedAndCut(n, _)
+            | CutMode::SetCuttingPosition(n, _)
+            | CutMode::FeedAndCutAndMoveToStart(n, _) => vec![self.m(), n],
+        }
+    }
+}
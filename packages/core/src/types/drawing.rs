@@ -0,0 +1,250 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::{le_u16, u8},
+    error::{ContextError, ErrMode},
+};
+
+use crate::commands::reader::error::ErrorCtx;
+
+/// A point in the Page mode print area, in horizontal/vertical motion units.
+///
+/// Which axis maps onto the paper feed direction depends on the start
+/// position selected with `ESC T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+pub struct DrawPoint {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl DrawPoint {
+    pub fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        (le_u16, le_u16).map(|(x, y)| Self { x, y })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 4] {
+        let [xl, xh] = self.x.to_le_bytes();
+        let [yl, yh] = self.y.to_le_bytes();
+        [xl, xh, yl, yh]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum LineStyle {
+    /// Single line, 1 dot wide
+    Thin = 1,
+    /// Single line, 2 dots wide
+    ModeratelyThick = 2,
+    /// Single line, 3 dots wide
+    Thick = 3,
+    /// Double line, 1 dot wide each
+    DoubleThin = 97,
+    /// Double line, 2 dots wide each
+    DoubleModeratelyThick = 98,
+    /// Double line, 3 dots wide each
+    DoubleThick = 99,
+}
+
+impl LineStyle {
+    pub fn from_bits(bits: u8) -> Option<LineStyle> {
+        Some(match bits {
+            1 => LineStyle::Thin,
+            2 => LineStyle::ModeratelyThick,
+            3 => LineStyle::Thick,
+            97 => LineStyle::DoubleThin,
+            98 => LineStyle::DoubleModeratelyThick,
+            99 => LineStyle::DoubleThick,
+
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum DrawColor {
+    /// Color 1 (black on most media)
+    Color1 = b'1',
+    /// Color 2 (red on two-color media)
+    Color2 = b'2',
+    /// Color 3
+    Color3 = b'3',
+}
+
+impl DrawColor {
+    pub fn from_bits(bits: u8) -> Option<DrawColor> {
+        Some(match bits {
+            b'1' => DrawColor::Color1,
+            b'2' => DrawColor::Color2,
+            b'3' => DrawColor::Color3,
+
+            _ => return None,
+        })
+    }
+}
+
+/// `GS ( Q` fn 48: a horizontal or vertical line drawn into the Page mode buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+pub struct Line {
+    pub start: DrawPoint,
+    pub end: DrawPoint,
+    pub style: LineStyle,
+    pub color: DrawColor,
+}
+
+impl Line {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        (
+            DrawPoint::parser(),
+            DrawPoint::parser(),
+            u8.verify_map(LineStyle::from_bits),
+            u8.verify_map(DrawColor::from_bits),
+        )
+            .map(|(start, end, style, color)| Self {
+                start,
+                end,
+                style,
+                color,
+            })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 10] {
+        let [x1l, x1h, y1l, y1h] = self.start.to_bytes();
+        let [x2l, x2h, y2l, y2h] = self.end.to_bytes();
+        [
+            x1l,
+            x1h,
+            y1l,
+            y1h,
+            x2l,
+            x2h,
+            y2l,
+            y2h,
+            self.style as u8,
+            self.color as u8,
+        ]
+    }
+}
+
+/// `GS ( Q` fn 49: a rectangle outline drawn into the Page mode buffer.
+///
+/// `start` must be strictly above and to the left of `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+pub struct Rectangle {
+    pub start: DrawPoint,
+    pub end: DrawPoint,
+    pub style: LineStyle,
+    pub color: DrawColor,
+    /// Color used to fill the inside, or `None` to leave it blank.
+    pub fill: Option<DrawColor>,
+}
+
+impl Rectangle {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        (
+            DrawPoint::parser(),
+            DrawPoint::parser(),
+            u8.verify_map(LineStyle::from_bits),
+            u8.verify_map(DrawColor::from_bits),
+            u8.verify_map(|v| match v {
+                b'0' => Some(None),
+                v => DrawColor::from_bits(v).map(Some),
+            }),
+        )
+            .map(|(start, end, style, color, fill)| Self {
+                start,
+                end,
+                style,
+                color,
+                fill,
+            })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 11] {
+        let [x1l, x1h, y1l, y1h] = self.start.to_bytes();
+        let [x2l, x2h, y2l, y2h] = self.end.to_bytes();
+        [
+            x1l,
+            x1h,
+            y1l,
+            y1h,
+            x2l,
+            x2h,
+            y2l,
+            y2h,
+            self.style as u8,
+            self.color as u8,
+            self.fill.map_or(b'0', |c| c as u8),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<'i, T>(
+        mut parser: impl Parser<Partial<&'i [u8]>, T, ErrMode<ContextError<ErrorCtx>>>,
+        bytes: &'i [u8],
+    ) -> Option<T> {
+        parser.parse_next(&mut Partial::new(bytes)).ok()
+    }
+
+    const STYLES: [LineStyle; 6] = [
+        LineStyle::Thin,
+        LineStyle::ModeratelyThick,
+        LineStyle::Thick,
+        LineStyle::DoubleThin,
+        LineStyle::DoubleModeratelyThick,
+        LineStyle::DoubleThick,
+    ];
+
+    #[test]
+    fn line_roundtrip() {
+        for style in STYLES {
+            for color in [DrawColor::Color1, DrawColor::Color2, DrawColor::Color3] {
+                let line = Line {
+                    start: DrawPoint::new(0, 300),
+                    end: DrawPoint::new(511, 300),
+                    style,
+                    color,
+                };
+                assert_eq!(parse(Line::parser(), &line.to_bytes()), Some(line));
+            }
+        }
+    }
+
+    #[test]
+    fn rectangle_roundtrip() {
+        for style in STYLES {
+            for fill in [None, Some(DrawColor::Color1), Some(DrawColor::Color3)] {
+                let rectangle = Rectangle {
+                    start: DrawPoint::new(8, 16),
+                    end: DrawPoint::new(400, 1000),
+                    style,
+                    color: DrawColor::Color2,
+                    fill,
+                };
+                assert_eq!(
+                    parse(Rectangle::parser(), &rectangle.to_bytes()),
+                    Some(rectangle)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_style_or_color() {
+        assert_eq!(parse(Line::parser(), b"\0\0\0\0\x10\0\0\0\x04\x31"), None);
+        assert_eq!(parse(Line::parser(), b"\0\0\0\0\x10\0\0\0\x01\x01"), None);
+        assert_eq!(
+            parse(Rectangle::parser(), b"\0\0\0\0\x10\0\x10\0\x01\x31\x34"),
+            None
+        );
+    }
+}
@@ -1,6 +1,14 @@
 use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    error::{ContextError, ErrMode},
+};
 
-use crate::state::{IntoState, State};
+use crate::{
+    commands::reader::error::ErrorCtx,
+    state::{IntoState, State},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
 #[repr(u8)]
@@ -28,6 +36,27 @@ impl Font {
             _ => None,
         }
     }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Self::from_n)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [self.to_n()]
+    }
+
+    /// The `n` byte that selects this font.
+    pub const fn to_n(self) -> u8 {
+        match self {
+            Font::A => 0,
+            Font::B => 1,
+            Font::C => 2,
+            Font::D => 3,
+            Font::E => 4,
+            Font::SpecialA => b'a',
+            Font::SpecialB => b'b',
+        }
+    }
 }
 
 impl IntoState for Font {
@@ -1,7 +1,16 @@
 use facet::Facet;
 use strum::{Display, EnumIter, FromRepr};
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    combinator::{dispatch, empty, fail},
+    error::{ContextError, ErrMode},
+};
 
-use crate::state::{IntoState, State};
+use crate::{
+    commands::reader::error::ErrorCtx,
+    state::{IntoState, State},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
 #[repr(u8)]
@@ -12,14 +21,18 @@ pub enum Justification {
 }
 
 impl Justification {
-    pub fn from_bits(bits: u8) -> Option<Justification> {
-        Some(match bits {
-            0 | b'0' => Justification::LeftJustified,
-            1 | b'1' => Justification::Centered,
-            2 | b'2' => Justification::RightJustified,
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        dispatch! {u8;
+            0 | b'0' => empty.value(Justification::LeftJustified),
+            1 | b'1' => empty.value(Justification::Centered),
+            2 | b'2' => empty.value(Justification::RightJustified),
 
-            _ => return None,
-        })
+            _ => fail,
+        }
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
     }
 }
 
@@ -1,14 +1,22 @@
+pub mod barcode;
 pub mod basic_styles;
 pub mod batch_print;
 pub mod bit_image_band;
 pub mod character_set;
 pub mod cut_mode;
+pub mod drawing;
 pub mod font;
 pub mod justification;
+pub mod maintenance_counter;
 pub mod print_area;
 pub mod print_color;
 pub mod print_direction;
+pub mod raster;
+pub mod printer_info;
 pub mod pulse_info;
+pub mod realtime_commands;
 pub mod realtime_request;
 pub mod requested_status;
+pub mod response;
+pub mod symbol;
 pub mod user_defined_characters;
@@ -7,7 +7,7 @@ use winnow::{
 
 use crate::commands::reader::error::ErrorCtx;
 
-#[derive(Clone, Copy, Facet)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Facet)]
 pub struct PrintArea {
     pub x: u16,
     pub y: u16,
@@ -19,4 +19,12 @@ impl<'a> PrintArea {
     pub fn parser() -> impl Parser<Partial<&'a [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
         (le_u16, le_u16, le_u16, le_u16).map(|(x, y, dx, dy)| Self { x, y, dx, dy })
     }
+
+    pub fn to_bytes(&self) -> [u8; 8] {
+        let [xl, xh] = self.x.to_le_bytes();
+        let [yl, yh] = self.y.to_le_bytes();
+        let [dxl, dxh] = self.dx.to_le_bytes();
+        let [dyl, dyh] = self.dy.to_le_bytes();
+        [xl, xh, yl, yh, dxl, dxh, dyl, dyh]
+    }
 }
@@ -1,5 +1,12 @@
 use facet::Facet;
 use strum::{Display, EnumIter, FromRepr};
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    error::{ContextError, ErrMode},
+};
+
+use crate::commands::reader::error::ErrorCtx;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
 #[repr(u8)]
@@ -21,4 +28,12 @@ impl PrintDirection {
             _ => return None,
         })
     }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Self::from_bits)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
 }
@@ -0,0 +1,70 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    combinator::{dispatch, empty, fail},
+    error::{ContextError, ErrMode},
+};
+
+use crate::commands::reader::error::ErrorCtx;
+
+/// Information requested with `GS I`.
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PrinterInfo {
+    /// Printer model ID (1 byte).
+    ModelId,
+    /// Type ID (1 byte): multi-byte character support, autocutter, display, ...
+    TypeId,
+    /// Version ID (1 byte). Superseded by [`PrinterInfo::FirmwareVersion`].
+    VersionId,
+    /// Firmware version string.
+    FirmwareVersion,
+    /// Manufacturer name string.
+    Manufacturer,
+    /// Printer name string.
+    ModelName,
+    /// Serial number string.
+    SerialNumber,
+    /// Supported font of language string.
+    FontOfLanguage,
+}
+
+impl PrinterInfo {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        dispatch! {u8;
+            1 | b'1' => empty.value(PrinterInfo::ModelId),
+            2 | b'2' => empty.value(PrinterInfo::TypeId),
+            3 | b'3' => empty.value(PrinterInfo::VersionId),
+            b'A' => empty.value(PrinterInfo::FirmwareVersion),
+            b'B' => empty.value(PrinterInfo::Manufacturer),
+            b'C' => empty.value(PrinterInfo::ModelName),
+            b'D' => empty.value(PrinterInfo::SerialNumber),
+            b'E' => empty.value(PrinterInfo::FontOfLanguage),
+
+            _ => fail,
+        }
+    }
+
+    pub fn to_byte(&self) -> u8 {
+        match self {
+            Self::ModelId => 1,
+            Self::TypeId => 2,
+            Self::VersionId => 3,
+            Self::FirmwareVersion => b'A',
+            Self::Manufacturer => b'B',
+            Self::ModelName => b'C',
+            Self::SerialNumber => b'D',
+            Self::FontOfLanguage => b'E',
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 1] {
+        [self.to_byte()]
+    }
+
+    /// Whether the printer answers with a `_ ... NUL` framed string rather than a single byte.
+    pub fn is_string(&self) -> bool {
+        !matches!(self, Self::ModelId | Self::TypeId | Self::VersionId)
+    }
+}
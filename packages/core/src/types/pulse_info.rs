@@ -1,6 +1,7 @@
 use facet::Facet;
 use winnow::{
     Parser, Partial,
+    binary::u8,
     combinator::{dispatch, empty, fail},
     error::{ContextError, ErrMode},
     token::take,
@@ -8,22 +9,56 @@ use winnow::{
 
 use crate::commands::reader::error::ErrorCtx;
 
-#[derive(Facet, Clone, Copy)]
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum PulseConnector {
     Pin2 = 0,
     Pin5 = 1,
 }
 
-#[derive(Facet, Clone, Copy)]
+impl PulseConnector {
+    pub fn from_bits(bits: u8) -> Option<PulseConnector> {
+        Some(match bits {
+            0 | b'0' => PulseConnector::Pin2,
+            1 | b'1' => PulseConnector::Pin5,
+
+            _ => return None,
+        })
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Self::from_bits)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
+
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RealtimePulseInfo(PulseConnector, u8);
 
+impl RealtimePulseInfo {
+    pub fn connector(&self) -> PulseConnector {
+        self.0
+    }
+
+    /// Pulse ON time, in units of 100 ms.
+    pub fn time(&self) -> u8 {
+        self.1
+    }
+}
+
 impl RealtimePulseInfo {
     pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
         dispatch!(take(2usize).map(|v: &[u8]| (v[0], v[1]));
-            (0, v @ 1..8) => empty.value(RealtimePulseInfo(PulseConnector::Pin2, v)),
-            (1, v @ 1..8) => empty.value(RealtimePulseInfo(PulseConnector::Pin5, v)),
+            (0, v @ 1..=8) => empty.value(RealtimePulseInfo(PulseConnector::Pin2, v)),
+            (1, v @ 1..=8) => empty.value(RealtimePulseInfo(PulseConnector::Pin5, v)),
             _ => fail,
         )
     }
+
+    pub fn to_bytes(&self) -> [u8; 2] {
+        [self.0 as u8, self.1]
+    }
 }
@@ -0,0 +1,239 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::{le_u16, u8},
+    error::{ContextError, ErrMode},
+    token::take,
+};
+
+use crate::{commands::reader::error::ErrorCtx, types::drawing::DrawColor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum RasterScale {
+    /// 1 × 1
+    Normal = 0,
+    /// 2 × 1
+    DoubleWidth = 1,
+    /// 1 × 2
+    DoubleHeight = 2,
+    /// 2 × 2
+    Quadruple = 3,
+}
+
+impl RasterScale {
+    /// Accepts both `0`–`3` and `'0'`–`'3'`.
+    pub fn from_bits(bits: u8) -> Option<RasterScale> {
+        Some(match bits {
+            0 | b'0' => RasterScale::Normal,
+            1 | b'1' => RasterScale::DoubleWidth,
+            2 | b'2' => RasterScale::DoubleHeight,
+            3 | b'3' => RasterScale::Quadruple,
+
+            _ => return None,
+        })
+    }
+}
+
+/// `GS v 0`: a raster bit image, printed as soon as it's received.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct RasterImage {
+    pub scale: RasterScale,
+    /// Width in bytes, 8 dots each.
+    pub width_bytes: u16,
+    /// Height in dots.
+    pub height: u16,
+    /// Row-major, MSB leftmost, `width_bytes` bytes per row. A set bit is printed.
+    pub data: Vec<u8>,
+}
+
+impl RasterImage {
+    pub fn new(scale: RasterScale, width_bytes: u16, height: u16, data: Vec<u8>) -> Self {
+        Self {
+            scale,
+            width_bytes,
+            height,
+            data,
+        }
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        RasterImageRef::parser().map(|image| image.into_owned())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            &[self.scale as u8][..],
+            &self.width_bytes.to_le_bytes(),
+            &self.height.to_le_bytes(),
+            &self.data,
+        ]
+        .concat()
+    }
+}
+
+/// `GS ( L` fn 112: monochrome graphics in raster format, stored in the print buffer until
+/// `GS ( L` fn 50 prints them.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct GraphicsData {
+    /// Horizontal and vertical scale, each 1 or 2.
+    pub scale: (u8, u8),
+    pub color: DrawColor,
+    /// Width in dots.
+    pub width: u16,
+    /// Height in dots.
+    pub height: u16,
+    /// Row-major, MSB leftmost, `width` rounded up to whole bytes per row. A set bit is printed.
+    pub data: Vec<u8>,
+}
+
+impl GraphicsData {
+    /// Tone `a` for monochrome data; multiple tone graphics aren't supported.
+    const MONOCHROME: u8 = 48;
+
+    pub fn new(width: u16, height: u16, data: Vec<u8>) -> Self {
+        Self {
+            scale: (1, 1),
+            color: DrawColor::Color1,
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        GraphicsDataRef::parser().map(|graphics| graphics.into_owned())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            &[
+                Self::MONOCHROME,
+                self.scale.0,
+                self.scale.1,
+                self.color as u8,
+            ][..],
+            &self.width.to_le_bytes(),
+            &self.height.to_le_bytes(),
+            &self.data,
+        ]
+        .concat()
+    }
+}
+
+/// A `GS v 0` raster image borrowed from the input.
+#[derive(Clone, Copy, Debug)]
+pub struct RasterImageRef<'i> {
+    scale: RasterScale,
+    width_bytes: u16,
+    height: u16,
+    /// Row-major, MSB leftmost, as in [`RasterImage::data`].
+    data: &'i [u8],
+}
+
+impl<'i> RasterImageRef<'i> {
+    pub fn parser() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        move |input: &mut Partial<&'i [u8]>| {
+            let scale = u8.verify_map(RasterScale::from_bits).parse_next(input)?;
+            let width_bytes = le_u16.parse_next(input)?;
+            let height = le_u16.parse_next(input)?;
+            let data = take(width_bytes as usize * height as usize).parse_next(input)?;
+
+            Ok(Self {
+                scale,
+                width_bytes,
+                height,
+                data,
+            })
+        }
+    }
+
+    pub fn scale(&self) -> RasterScale {
+        self.scale
+    }
+
+    pub fn width_bytes(&self) -> u16 {
+        self.width_bytes
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn data(&self) -> &'i [u8] {
+        self.data
+    }
+
+    pub fn into_owned(self) -> RasterImage {
+        RasterImage::new(
+            self.scale,
+            self.width_bytes,
+            self.height,
+            self.data.to_vec(),
+        )
+    }
+}
+
+/// `GS ( L` fn 112 graphics data borrowed from the input.
+#[derive(Clone, Copy, Debug)]
+pub struct GraphicsDataRef<'i> {
+    scale: (u8, u8),
+    color: DrawColor,
+    width: u16,
+    height: u16,
+    /// Row-major, MSB leftmost, as in [`GraphicsData::data`].
+    data: &'i [u8],
+}
+
+impl<'i> GraphicsDataRef<'i> {
+    pub fn parser() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        move |input: &mut Partial<&'i [u8]>| {
+            u8.verify(|a| *a == GraphicsData::MONOCHROME)
+                .parse_next(input)?;
+            let bx = u8.verify(|b| matches!(b, 1 | 2)).parse_next(input)?;
+            let by = u8.verify(|b| matches!(b, 1 | 2)).parse_next(input)?;
+            let color = u8.verify_map(DrawColor::from_bits).parse_next(input)?;
+            let width = le_u16.parse_next(input)?;
+            let height = le_u16.parse_next(input)?;
+            let data = take((width as usize).div_ceil(8) * height as usize).parse_next(input)?;
+
+            Ok(Self {
+                scale: (bx, by),
+                color,
+                width,
+                height,
+                data,
+            })
+        }
+    }
+
+    pub fn scale(&self) -> (u8, u8) {
+        self.scale
+    }
+
+    pub fn color(&self) -> DrawColor {
+        self.color
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn data(&self) -> &'i [u8] {
+        self.data
+    }
+
+    pub fn into_owned(self) -> GraphicsData {
+        GraphicsData {
+            scale: self.scale,
+            color: self.color,
+            width: self.width,
+            height: self.height,
+            data: self.data.to_vec(),
+        }
+    }
+}
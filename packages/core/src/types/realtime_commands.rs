@@ -0,0 +1,93 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::{le_u16, u8},
+    combinator::{dispatch, empty, fail, repeat},
+    error::{ContextError, ErrMode},
+};
+
+use crate::commands::reader::error::ErrorCtx;
+
+/// Real-time commands that can be switched on and off with `GS ( D`.
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RealtimeCommandKind {
+    /// `DLE DC4` (fn = 1)
+    GeneratePulse = 1,
+    /// `DLE DC4` (fn = 2)
+    PowerOffSequence = 2,
+}
+
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RealtimeCommandSetting {
+    pub kind: RealtimeCommandKind,
+    pub enabled: bool,
+}
+
+impl<'i> RealtimeCommandSetting {
+    pub fn sequence_parser()
+    -> impl Parser<Partial<&'i [u8]>, Vec<RealtimeCommandSetting>, ErrMode<ContextError<ErrorCtx>>>
+    {
+        move |input: &mut Partial<&'i [u8]>| {
+            let len = le_u16
+                .verify(|len| *len >= 3 && len % 2 == 1)
+                .parse_next(input)?;
+            let _m = 20u8.parse_next(input)?;
+
+            repeat(
+                ((len - 1) / 2) as usize,
+                (
+                    dispatch! {u8;
+                        1 => empty.value(RealtimeCommandKind::GeneratePulse),
+                        2 => empty.value(RealtimeCommandKind::PowerOffSequence),
+
+                        _ => fail,
+                    },
+                    dispatch! {u8;
+                        0 | b'0' => empty.value(false),
+                        1 | b'1' => empty.value(true),
+
+                        _ => fail,
+                    },
+                )
+                    .map(|(kind, enabled)| RealtimeCommandSetting { kind, enabled }),
+            )
+            .parse_next(input)
+        }
+    }
+}
+
+/// Which real-time commands the printer currently processes as soon as they are received.
+///
+/// `DLE EOT`, `DLE ENQ` and the remaining `DLE DC4` functions are always enabled;
+/// only the two functions controlled by `GS ( D` can be switched off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RealtimeCommands {
+    pub generate_pulse: bool,
+    pub power_off_sequence: bool,
+}
+
+impl Default for RealtimeCommands {
+    fn default() -> Self {
+        Self {
+            generate_pulse: true,
+            power_off_sequence: true,
+        }
+    }
+}
+
+impl RealtimeCommands {
+    pub fn is_enabled(&self, kind: RealtimeCommandKind) -> bool {
+        match kind {
+            RealtimeCommandKind::GeneratePulse => self.generate_pulse,
+            RealtimeCommandKind::PowerOffSequence => self.power_off_sequence,
+        }
+    }
+
+    pub fn apply(&mut self, setting: RealtimeCommandSetting) {
+        match setting.kind {
+            RealtimeCommandKind::GeneratePulse => self.generate_pulse = setting.enabled,
+            RealtimeCommandKind::PowerOffSequence => self.power_off_sequence = setting.enabled,
+        }
+    }
+}
@@ -7,7 +7,7 @@ use winnow::{
 
 use crate::commands::reader::error::ErrorCtx;
 
-#[derive(Facet, Clone, Copy, Debug)]
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(C)]
 pub enum RealtimeRequest {
     /// Equivalent to pressing the FEED button during recovery-wait.
@@ -28,4 +28,8 @@ impl RealtimeRequest {
             0x02.value(RealtimeRequest::Reset),
         ))
     }
+
+    pub fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
 }
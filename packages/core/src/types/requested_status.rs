@@ -8,7 +8,7 @@ use winnow::{
 
 use crate::commands::reader::error::ErrorCtx;
 
-#[derive(Facet, Clone, Copy, Debug)]
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(C)]
 pub enum RequestedStatus {
     Printer,
@@ -45,4 +45,46 @@ impl RequestedStatus {
             _ => winnow::combinator::fail, // unknown top-level tag
         }
     }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match *self {
+            RequestedStatus::Printer => vec![0x01],
+            RequestedStatus::OfflineCause => vec![0x02],
+            RequestedStatus::ErrorCause => vec![0x03],
+            RequestedStatus::RollPaperSensor => vec![0x04],
+            RequestedStatus::Ink { channel } => vec![0x07, channel],
+            RequestedStatus::Peeler => vec![0x08, 0x03],
+            RequestedStatus::Interface => vec![0x12, 0x01],
+            RequestedStatus::DisplayModule => vec![0x12, 0x03],
+        }
+    }
+}
+
+/// Status requested with `GS r`.
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TransmittedStatus {
+    PaperSensor,
+    DrawerKickOutConnector,
+    Ink,
+}
+
+impl TransmittedStatus {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        dispatch! {u8;
+            1 | b'1' => empty.value(TransmittedStatus::PaperSensor),
+            2 | b'2' => empty.value(TransmittedStatus::DrawerKickOutConnector),
+            4 | b'4' => empty.value(TransmittedStatus::Ink),
+
+            _ => winnow::combinator::fail,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 1] {
+        [match self {
+            TransmittedStatus::PaperSensor => 1,
+            TransmittedStatus::DrawerKickOutConnector => 2,
+            TransmittedStatus::Ink => 4,
+        }]
+    }
 }
@@ -0,0 +1,54 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    combinator::{dispatch, empty, fail},
+    error::{ContextError, ErrMode},
+    token::take,
+};
+
+use crate::commands::reader::error::ErrorCtx;
+
+/// Process ID echoed back by the printer once the preceding data is processed (`GS ( H` fn 48).
+///
+/// Each byte must be a printable ASCII character (32–126).
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProcessId(pub [u8; 4]);
+
+impl ProcessId {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        take(4usize)
+            .verify(|v: &[u8]| v.iter().all(|b| (32..=126).contains(b)))
+            .map(|v: &[u8]| ProcessId([v[0], v[1], v[2], v[3]]))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 4] {
+        self.0
+    }
+}
+
+/// Offline response transmission setting (`GS ( H` fn 49).
+#[derive(Facet, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OfflineResponse {
+    Disabled,
+    Enabled,
+    /// Offline responses also carry the offline cause.
+    EnabledWithCause,
+}
+
+impl OfflineResponse {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        dispatch! {u8;
+            0 | b'0' => empty.value(OfflineResponse::Disabled),
+            1 | b'1' => empty.value(OfflineResponse::Enabled),
+            2 | b'2' => empty.value(OfflineResponse::EnabledWithCause),
+
+            _ => fail,
+        }
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
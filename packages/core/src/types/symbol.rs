@@ -0,0 +1,174 @@
+use facet::Facet;
+use winnow::{
+    Parser, Partial,
+    binary::u8,
+    error::{ContextError, ErrMode},
+    token::rest,
+};
+
+use crate::commands::reader::error::ErrorCtx;
+
+/// `GS ( k` fn 165: the QR Code model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum QrModel {
+    Model1 = 49,
+    Model2 = 50,
+    Micro = 51,
+}
+
+impl QrModel {
+    pub fn from_bits(bits: u8) -> Option<QrModel> {
+        Some(match bits {
+            49 => QrModel::Model1,
+            50 => QrModel::Model2,
+            51 => QrModel::Micro,
+
+            _ => return None,
+        })
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Self::from_bits)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
+
+/// `GS ( k` fn 169: the QR Code error correction level, by the share of codewords that can be
+/// restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Facet)]
+#[repr(u8)]
+pub enum QrErrorCorrection {
+    /// About 7%.
+    L = 48,
+    /// About 15%.
+    #[default]
+    M = 49,
+    /// About 25%.
+    Q = 50,
+    /// About 30%.
+    H = 51,
+}
+
+impl QrErrorCorrection {
+    pub fn from_bits(bits: u8) -> Option<QrErrorCorrection> {
+        Some(match bits {
+            48 => QrErrorCorrection::L,
+            49 => QrErrorCorrection::M,
+            50 => QrErrorCorrection::Q,
+            51 => QrErrorCorrection::H,
+
+            _ => return None,
+        })
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        u8.verify_map(Self::from_bits)
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
+
+/// `GS ( k` fn 069: the PDF417 error correction level, either fixed or in proportion to the
+/// amount of data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum Pdf417ErrorCorrection {
+    /// Level 0–8, for 2 to 512 error correction codewords.
+    Level(u8),
+    /// Tenths of the number of data codewords, 1–40; the level is derived from it.
+    Ratio(u8),
+}
+
+impl Default for Pdf417ErrorCorrection {
+    fn default() -> Self {
+        Pdf417ErrorCorrection::Ratio(1)
+    }
+}
+
+impl Pdf417ErrorCorrection {
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        (u8, u8).verify_map(|(m, n)| match (m, n) {
+            (48, 48..=56) => Some(Pdf417ErrorCorrection::Level(n - 48)),
+            (49, 1..=40) => Some(Pdf417ErrorCorrection::Ratio(n)),
+
+            _ => None,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 2] {
+        match *self {
+            Pdf417ErrorCorrection::Level(level) => [48, 48 + level],
+            Pdf417ErrorCorrection::Ratio(ratio) => [49, ratio],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Facet)]
+#[repr(u8)]
+pub enum DataMatrixShape {
+    #[default]
+    Square = 48,
+    Rectangle = 49,
+}
+
+/// `GS ( k` fn 666: the DataMatrix symbol type and size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Facet)]
+pub struct DataMatrixSymbolType {
+    pub shape: DataMatrixShape,
+    /// Columns, or 0 to fit the data.
+    pub columns: u8,
+    /// Rows of a rectangle, or 0 to fit the data. Always 0 for a square.
+    pub rows: u8,
+}
+
+impl DataMatrixSymbolType {
+    pub fn new(shape: DataMatrixShape, columns: u8, rows: u8) -> Self {
+        Self {
+            shape,
+            columns,
+            rows,
+        }
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        (u8, u8, u8).verify_map(|(m, columns, rows)| {
+            let shape = match m {
+                48 => DataMatrixShape::Square,
+                49 => DataMatrixShape::Rectangle,
+
+                _ => return None,
+            };
+            Some(Self::new(shape, columns, rows))
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 3] {
+        [self.shape as u8, self.columns, self.rows]
+    }
+}
+
+/// `GS ( k` store functions: the symbol data, `d1…dk`, which runs to the end of the block.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+pub struct SymbolData {
+    pub data: Vec<u8>,
+}
+
+impl SymbolData {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    pub fn parser<'i>() -> impl Parser<Partial<&'i [u8]>, Self, ErrMode<ContextError<ErrorCtx>>> {
+        rest.map(|data: &[u8]| Self::new(data.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
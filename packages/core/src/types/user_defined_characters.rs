@@ -8,7 +8,7 @@ use winnow::{
 
 use crate::commands::reader::error::{ErrorCtx, ErrorExpected};
 
-#[derive(Clone, Facet)]
+#[derive(Clone, Debug, PartialEq, Eq, Facet)]
 pub struct UserDefinedCharacter {
     character: u8,
     character_width: u8,
@@ -19,70 +19,169 @@ pub struct UserDefinedCharacter {
     canvas: Vec<u8>,
 }
 
+impl UserDefinedCharacter {
+    pub fn character(&self) -> u8 {
+        self.character
+    }
+
+    pub fn width(&self) -> u8 {
+        self.character_width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.canvas_height
+    }
+
+    pub fn canvas(&self) -> &[u8] {
+        &self.canvas
+    }
+
+    /// The canvas packed back into column format: `height / 8` bytes per column.
+    pub fn to_data(&self) -> Vec<u8> {
+        let x = self.character_width as usize;
+        let y = (self.canvas_height as usize).div_ceil(8);
+        let mut data = vec![0u8; x * y];
+
+        for row in 0..self.canvas_height as usize {
+            for col in 0..x {
+                if self.canvas[row * x + col] != 0 {
+                    data[col * y + row / 8] |= 0x80 >> (row % 8);
+                }
+            }
+        }
+
+        data
+    }
+}
+
 impl<'i> UserDefinedCharacter {
     pub fn sequence_parser()
-    -> impl Parser<Partial<&'i [u8]>, Vec<UserDefinedCharacter>, ErrMode<ContextError<ErrorCtx>>> {
+    -> impl Parser<Partial<&'i [u8]>, Vec<UserDefinedCharacter>, ErrMode<ContextError<ErrorCtx>>>
+    {
+        UserDefinedCharactersRef::parser().map(|seq| seq.iter().map(|c| c.into_owned()).collect())
+    }
+}
+
+/// The characters defined by one `ESC &`, borrowed from the input in column format.
+///
+/// `ESC & y c1 c2 [x d1…d(y × x)]k`: every character is preceded by its own width `x`, so
+/// characters of one command can differ in width. This used to be read as a single `x` after
+/// `c2`, which only decoded commands defining one character, or several of the same width,
+/// correctly.
+#[derive(Clone, Copy, Debug)]
+pub struct UserDefinedCharactersRef<'i> {
+    y: u8,
+    c1: u8,
+    c2: u8,
+    /// `[x d1...d(y × x)]` for each character from c1 to c2
+    data: &'i [u8],
+}
+
+impl<'i> UserDefinedCharactersRef<'i> {
+    pub fn parser()
+    -> impl Parser<Partial<&'i [u8]>, UserDefinedCharactersRef<'i>, ErrMode<ContextError<ErrorCtx>>>
+    {
         move |input: &mut Partial<&'i [u8]>| {
             let y = u8.parse_next(input)?;
             let c1 = u8.parse_next(input)?;
             let c2 = u8.parse_next(input)?;
-            let x = u8.parse_next(input)?;
 
             // TODO: Error handling
             // Fail when:
-            //   y == 0 || x == 0
+            //   y == 0
             //   c2 < c1
             //   c1 < 32
             //   c2 > 126
 
-            let k = (c2 - c1) + 1;
-            let bpc = (y as u16) * (x as u16); // bytes per character (column-major, y bytes per column)
-            let len = ((bpc as u32) * (k as u32)) as usize;
+            // Every character carries its own width, so walk them once to find the total length.
+            let mut probe = *input;
+            let mut len = 0usize;
+            for _ in c1..=c2 {
+                let x = u8.parse_next(&mut probe)?;
+                let bpc = (y as usize) * (x as usize); // bytes per character (column-major, y bytes per column)
+                take(bpc).parse_next(&mut probe)?;
+                len += 1 + bpc;
+            }
 
             let data = take(len).parse_next(input)?;
-            let x_usize = x as usize;
-            let y_usize = y as usize;
-            let bpc_usize = bpc as usize;
-            let canvas_height: u16 = (y as u16) * 8;
-            let canvas_h_usize = canvas_height as usize;
-
-            let mut out: Vec<UserDefinedCharacter> = Vec::with_capacity(k as usize);
-
-            for (idx, ch) in (c1..=c2).enumerate() {
-                let base = idx * bpc_usize;
-                let slice = &data[base..base + bpc_usize];
-
-                // Row-major canvas: rows = canvas_h_usize, cols = x_usize
-                let mut canvas = vec![0u8; canvas_h_usize * x_usize];
-
-                // Input is column-major: for each column (0..x), there are `y` bytes top→down.
-                for col in 0..x_usize {
-                    for by in 0..y_usize {
-                        let b = slice[col * y_usize + by];
-                        // MSB..LSB map to top..bottom rows within this 8-dot block
-                        for bit in 0..8 {
-                            let row = by * 8 + bit;
-                            if row >= canvas_h_usize {
-                                break;
-                            }
-                            let on = (b >> (7 - bit)) & 1 == 1;
-                            if on {
-                                let idx_rm = row * x_usize + col; // row-major index
-                                canvas[idx_rm] = 1;
-                            }
-                        }
-                    }
-                }
 
-                out.push(UserDefinedCharacter {
-                    character: ch,
-                    character_width: x,
-                    canvas_height,
-                    canvas,
-                });
+            Ok(UserDefinedCharactersRef { y, c1, c2, data })
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = UserDefinedCharacterRef<'i>> + use<'i> {
+        let y = self.y;
+        let mut data = self.data;
+
+        (self.c1..=self.c2).map(move |character| {
+            let x = data[0];
+            let len = (y as usize) * (x as usize);
+            let character_data = &data[1..1 + len];
+            data = &data[1 + len..];
+
+            UserDefinedCharacterRef {
+                character,
+                y,
+                x,
+                data: character_data,
             }
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct UserDefinedCharacterRef<'i> {
+    character: u8,
+    y: u8,
+    x: u8,
+    /// Column-major: for each column (0..x), there are `y` bytes top→down.
+    data: &'i [u8],
+}
+
+impl<'i> UserDefinedCharacterRef<'i> {
+    pub fn character(&self) -> u8 {
+        self.character
+    }
+
+    pub fn width(&self) -> u8 {
+        self.x
+    }
+
+    pub fn height(&self) -> u16 {
+        (self.y as u16) * 8
+    }
+
+    pub fn dot(&self, col: u8, row: u16) -> bool {
+        if col >= self.x || row >= self.height() {
+            return false;
+        }
+
+        let b = self.data[(col as usize) * (self.y as usize) + (row as usize) / 8];
+
+        // MSB..LSB map to top..bottom rows within this 8-dot block
+        (b >> (7 - (row % 8))) & 1 == 1
+    }
+
+    pub fn into_owned(self) -> UserDefinedCharacter {
+        let canvas_height = self.height();
+        let x_usize = self.x as usize;
+
+        // Row-major canvas: rows = canvas_height, cols = x
+        let mut canvas = vec![0u8; (canvas_height as usize) * x_usize];
+
+        for row in 0..canvas_height {
+            for col in 0..self.x {
+                if self.dot(col, row) {
+                    canvas[(row as usize) * x_usize + (col as usize)] = 1;
+                }
+            }
+        }
 
-            Ok(out)
+        UserDefinedCharacter {
+            character: self.character,
+            character_width: self.x,
+            canvas_height,
+            canvas,
         }
     }
 }
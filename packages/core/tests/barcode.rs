@@ -0,0 +1,72 @@
+use thermal::{
+    state::effect::barcode::{Barcode, BarcodeError, Symbology},
+    types::barcode::BarcodeSystem,
+};
+
+fn encoded(symbology: Symbology, data: &str) -> Vec<u8> {
+    let encoded = Barcode::new(symbology, data).encode().unwrap();
+    assert_eq!(encoded.system, symbology.system());
+    encoded.data
+}
+
+#[test]
+fn upc_a_check_digit() {
+    assert_eq!(encoded(Symbology::UpcA, "03600029145"), b"036000291452");
+    assert_eq!(encoded(Symbology::UpcA, "036000291452"), b"036000291452");
+    assert_eq!(
+        Barcode::new(Symbology::UpcA, "036000291453").encode(),
+        Err(BarcodeError::CheckDigit {
+            symbology: Symbology::UpcA,
+            expected: '2',
+            found: '3',
+        })
+    );
+}
+
+#[test]
+fn ean13_check_digit() {
+    assert_eq!(encoded(Symbology::Ean13, "400638133393"), b"4006381333931");
+    assert_eq!(
+        Barcode::new(Symbology::Ean13, "40063813339").encode(),
+        Err(BarcodeError::Length {
+            symbology: Symbology::Ean13,
+            len: 11,
+            expected: "12 or 13 digits",
+        })
+    );
+}
+
+#[test]
+fn upc_e_expands_for_its_check_digit() {
+    assert_eq!(encoded(Symbology::UpcE, "654321"), b"06543217");
+    assert_eq!(encoded(Symbology::UpcE, "0654321"), b"06543217");
+    assert_eq!(encoded(Symbology::UpcE, "06543217"), b"06543217");
+    assert_eq!(
+        Barcode::new(Symbology::UpcE, "1654321").encode(),
+        Err(BarcodeError::Character {
+            symbology: Symbology::UpcE,
+            character: '1',
+        })
+    );
+}
+
+#[test]
+fn code128_switches_to_code_set_c_for_digits() {
+    assert_eq!(
+        encoded(Symbology::Code128, "Order #12345678"),
+        [&b"{BOrder #{C"[..], &[12, 34, 56, 78]].concat()
+    );
+    assert_eq!(encoded(Symbology::Code128, "12"), [b'{', b'C', 12]);
+}
+
+#[test]
+fn code128_doubles_literal_braces() {
+    assert_eq!(encoded(Symbology::Code128, "a{b"), b"{Ba{{b");
+}
+
+#[test]
+fn encoded_data_is_length_prefixed() {
+    let encoded = Barcode::new(Symbology::Code39, "*ABC-1*").encode().unwrap();
+    assert_eq!(encoded.system, BarcodeSystem::Code39);
+    assert_eq!(encoded.to_bytes(), b"\x45\x07*ABC-1*");
+}
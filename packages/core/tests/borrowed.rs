@@ -0,0 +1,112 @@
+use thermal::{
+    commands::{
+        Command, GraphicsFunction, SymbolFunction,
+        reader::{
+            borrowed::{CommandRef, OutputRef},
+            state::{Mode, ParserState},
+        },
+    },
+    types::{
+        raster::{GraphicsData, RasterImage, RasterScale},
+        symbol::SymbolData,
+    },
+};
+use winnow::Partial;
+
+struct Standard;
+
+impl ParserState for Standard {
+    fn mode(&self) -> Mode {
+        Mode::Standard
+    }
+}
+
+fn parse_ref(bytes: &[u8]) -> CommandRef<'_> {
+    let mut input = Partial::new(bytes);
+    match Command::parse_ref(&mut input, &Standard).unwrap() {
+        OutputRef::Command(command) => {
+            assert!(input.is_empty());
+            command
+        }
+        OutputRef::Text(text) => panic!("parsed as text: {text:?}"),
+    }
+}
+
+fn written(command: &Command) -> Vec<u8> {
+    let mut bytes = vec![];
+    command.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn raster_image_is_borrowed() {
+    let data: Vec<u8> = (0..24).collect();
+    let command = Command::PrintRasterBitImage(RasterImage::new(
+        RasterScale::DoubleWidth,
+        3,
+        8,
+        data.clone(),
+    ));
+    let bytes = written(&command);
+
+    let CommandRef::PrintRasterBitImage(image) = parse_ref(&bytes) else {
+        panic!("not borrowed");
+    };
+    assert_eq!(image.data(), &data[..]);
+    assert_eq!(
+        image.into_owned(),
+        RasterImage::new(RasterScale::DoubleWidth, 3, 8, data)
+    );
+}
+
+#[test]
+fn graphics_data_is_borrowed() {
+    let data: Vec<u8> = (0..20).collect();
+    let command = Command::Graphics(GraphicsFunction::StoreTheGraphicsDataInThePrintBuffer(
+        GraphicsData::new(10, 10, data.clone()),
+    ));
+    let bytes = written(&command);
+
+    let borrowed = parse_ref(&bytes);
+    let CommandRef::StoreTheGraphicsDataInThePrintBuffer(graphics) = borrowed.clone() else {
+        panic!("not borrowed");
+    };
+    assert_eq!(graphics.data(), &data[..]);
+    assert_eq!(borrowed.into_owned(), command);
+}
+
+#[test]
+fn symbol_data_is_borrowed() {
+    let data = b"https://example.com/".to_vec();
+
+    for command in [
+        SymbolFunction::QrStoreData(SymbolData::new(data.clone())),
+        SymbolFunction::Pdf417StoreData(SymbolData::new(data.clone())),
+        SymbolFunction::DataMatrixStoreData(SymbolData::new(data.clone())),
+    ]
+    .map(Command::Symbol)
+    {
+        let bytes = written(&command);
+
+        let borrowed = parse_ref(&bytes);
+        let (CommandRef::QrStoreData(stored)
+        | CommandRef::Pdf417StoreData(stored)
+        | CommandRef::DataMatrixStoreData(stored)) = borrowed.clone()
+        else {
+            panic!("not borrowed");
+        };
+        assert_eq!(stored, &data[..]);
+        assert_eq!(borrowed.into_owned(), command);
+    }
+}
+
+#[test]
+fn other_symbol_functions_are_owned() {
+    let command = Command::Symbol(SymbolFunction::QrPrint);
+    let bytes = written(&command);
+
+    let CommandRef::Owned(owned) = parse_ref(&bytes) else {
+        panic!("borrowed");
+    };
+    assert_eq!(owned, command);
+}
@@ -0,0 +1,69 @@
+use thermal::{
+    commands::{RealtimeCommand, RealtimeCommandDiscriminants, reader::Output},
+    endpoint::{Connection, Endpoint, SendError},
+};
+
+/// Collects what it's sent, failing the next send while `fail` is set.
+#[derive(Default)]
+struct Flaky {
+    sent: Vec<u8>,
+    fail: bool,
+}
+
+impl Endpoint for Flaky {
+    type Error = &'static str;
+
+    fn send(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        if std::mem::take(&mut self.fail) {
+            return Err("disconnected");
+        }
+        self.sent.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_flush_keeps_the_queue() {
+    let mut connection = Connection::new(Flaky::default());
+    connection.send(&Output::Text(b"hello".to_vec())).unwrap();
+
+    connection.endpoint_mut().fail = true;
+    assert!(matches!(
+        connection.flush(),
+        Err(SendError::Endpoint("disconnected"))
+    ));
+    assert!(connection.endpoint().sent.is_empty());
+
+    connection.flush().unwrap();
+    assert_eq!(connection.endpoint().sent, b"hello");
+
+    connection.flush().unwrap();
+    assert_eq!(connection.endpoint().sent, b"hello");
+}
+
+#[test]
+fn failed_realtime_send_keeps_the_command() {
+    let mut connection = Connection::new(Flaky::default());
+    connection.send(&Output::Text(b"hello".to_vec())).unwrap();
+
+    connection.endpoint_mut().fail = true;
+    assert!(matches!(
+        connection.send_realtime(&RealtimeCommand::ExecutePowerOffSequence),
+        Err(SendError::Endpoint("disconnected"))
+    ));
+    assert_eq!(connection.awaiting_reply().count(), 0);
+    assert!(matches!(
+        connection.send_realtime(&RealtimeCommand::ExecutePowerOffSequence),
+        Err(SendError::AwaitingReply(
+            RealtimeCommandDiscriminants::ExecutePowerOffSequence
+        ))
+    ));
+
+    connection.flush().unwrap();
+    assert_eq!(connection.endpoint().sent, b"\x10\x14\x02\x01\x08hello");
+    assert!(
+        connection
+            .awaiting_reply()
+            .eq([RealtimeCommandDiscriminants::ExecutePowerOffSequence])
+    );
+}
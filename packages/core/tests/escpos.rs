@@ -0,0 +1,63 @@
+use thermal::{
+    commands::Command,
+    escpos,
+    types::{
+        basic_styles::BasicStyles,
+        character_set::AsciiVariant,
+        cut_mode::{CutMode, CuttingShape},
+        font::Font,
+    },
+};
+
+fn written(commands: &[Command]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for command in commands {
+        command.write_to(&mut bytes).unwrap();
+    }
+    bytes
+}
+
+#[test]
+fn character_commands() {
+    let bytes = escpos![
+        SelectPrintMode(0x39),
+        TurnUnderlineModeOnOff(2),
+        SelectCharacterFont(B),
+        SelectInternationalCharacterSet(Germany),
+        SelectCharacterSize(2, 3),
+        "Text\twith\r\n",
+    ];
+
+    let mut expected = written(&[
+        Command::SelectPrintMode(BasicStyles::from_bits(0x39)),
+        Command::TurnUnderlineModeOnOff(2),
+        Command::SelectCharacterFont(Font::B),
+        Command::SelectInternationalCharacterSet(AsciiVariant::Germany),
+        Command::SelectCharacterSize(2, 3),
+    ]);
+    expected.extend(b"Text\twith\r\n");
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn cut_modes() {
+    let bytes = escpos![
+        SelectCutModeAndCutPaper(Cut(Full)),
+        SelectCutModeAndCutPaper(Cut(Partial)),
+        SelectCutModeAndCutPaper(FeedAndCut(3, Partial)),
+        SelectCutModeAndCutPaper(SetCuttingPosition(0, Full)),
+        SelectCutModeAndCutPaper(FeedAndCutAndMoveToStart(255, Partial)),
+    ];
+
+    let expected = written(
+        &[
+            CutMode::Cut(CuttingShape::Full),
+            CutMode::Cut(CuttingShape::Partial),
+            CutMode::FeedAndCut(3, CuttingShape::Partial),
+            CutMode::SetCuttingPosition(0, CuttingShape::Full),
+            CutMode::FeedAndCutAndMoveToStart(255, CuttingShape::Partial),
+        ]
+        .map(Command::SelectCutModeAndCutPaper),
+    );
+    assert_eq!(bytes, expected);
+}
@@ -0,0 +1,71 @@
+use strum::IntoEnumIterator;
+use thermal::{
+    commands::{Command, Model},
+    types::character_set::{AsciiVariant, Codepage},
+};
+
+#[test]
+fn tm_t88v_international_character_sets() {
+    let supported: Vec<AsciiVariant> = AsciiVariant::iter()
+        .filter(|v| {
+            Command::SelectInternationalCharacterSet(*v).supported_on(Model::TmT88v) == Some(true)
+        })
+        .collect();
+
+    assert_eq!(
+        supported,
+        [
+            AsciiVariant::Usa,
+            AsciiVariant::France,
+            AsciiVariant::Germany,
+            AsciiVariant::Uk,
+            AsciiVariant::Denmark1,
+            AsciiVariant::Sweden,
+            AsciiVariant::Italy,
+            AsciiVariant::Spain1,
+            AsciiVariant::Japan,
+            AsciiVariant::Norway,
+            AsciiVariant::Denmark2,
+            AsciiVariant::Spain2,
+            AsciiVariant::LatinAmerica,
+            AsciiVariant::Korea,
+            AsciiVariant::SloveniaCroatia,
+            AsciiVariant::China,
+            AsciiVariant::Vietnam,
+            AsciiVariant::Arabia,
+        ]
+    );
+}
+
+#[test]
+fn tm_t88v_codepages() {
+    let supported: Vec<Codepage> = Codepage::iter()
+        .filter(|p| Command::SelectCharacterCodeTable(*p).supported_on(Model::TmT88v) == Some(true))
+        .collect();
+
+    assert_eq!(
+        supported,
+        [
+            Codepage::Page0_Pc437,
+            Codepage::Page1_Katakana,
+            Codepage::Page2_Pc850,
+            Codepage::Page3_Pc860,
+            Codepage::Page4_Pc863,
+            Codepage::Page5_Pc865,
+            Codepage::Page11_Pc851,
+            Codepage::Page12_Pc853,
+            Codepage::Page13_Pc857,
+            Codepage::Page14_Pc737,
+            Codepage::Page15_Iso8859_7,
+            Codepage::Page16_Wpc1252,
+            Codepage::Page17_Pc866,
+            Codepage::Page18_Pc852,
+            Codepage::Page19_Pc858,
+        ]
+    );
+}
+
+#[test]
+fn missing_model_data_is_unknown() {
+    assert_eq!(Command::InitializePrinter.supported_on(Model::TmT88v), None);
+}
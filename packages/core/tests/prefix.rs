@@ -0,0 +1,28 @@
+use thermal::commands::CommandDiscriminants;
+
+#[test]
+fn select_international_character_set() {
+    assert_eq!(
+        CommandDiscriminants::from_prefix(&[0x1B, 0x52, 0x00]),
+        &[CommandDiscriminants::SelectInternationalCharacterSet]
+    );
+    assert_eq!(
+        CommandDiscriminants::SelectInternationalCharacterSet.ascii_format(),
+        &["ESC R"]
+    );
+    assert!(CommandDiscriminants::from_prefix(&[0x1B, 0x0D]).is_empty());
+}
+
+#[test]
+fn functions_share_their_command_prefix() {
+    let commands = CommandDiscriminants::from_prefix(&[0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x51]);
+    assert_eq!(commands, &[CommandDiscriminants::Symbol]);
+}
+
+#[test]
+fn longest_prefix_wins() {
+    assert_eq!(
+        CommandDiscriminants::from_prefix(&[0x1D, 0x76, 0x30, 0x00]),
+        &[CommandDiscriminants::PrintRasterBitImage]
+    );
+}
@@ -0,0 +1,232 @@
+use thermal::{
+    state::effect::symbol::qr::{Matrix, encode},
+    types::symbol::QrErrorCorrection,
+};
+
+/// The format information for each mask, from the table in ISO/IEC 18004 Annex C.
+const FORMAT: [(QrErrorCorrection, [u32; 8]); 4] = [
+    (
+        QrErrorCorrection::L,
+        [
+            0b111011111000100,
+            0b111001011110011,
+            0b111110110101010,
+            0b111100010011101,
+            0b110011000101111,
+            0b110001100011000,
+            0b110110001000001,
+            0b110100101110110,
+        ],
+    ),
+    (
+        QrErrorCorrection::M,
+        [
+            0b101010000010010,
+            0b101000100100101,
+            0b101111001111100,
+            0b101101101001011,
+            0b100010111111001,
+            0b100000011001110,
+            0b100111110010111,
+            0b100101010100000,
+        ],
+    ),
+    (
+        QrErrorCorrection::Q,
+        [
+            0b011010101011111,
+            0b011000001101000,
+            0b011111100110001,
+            0b011101000000110,
+            0b010010010110100,
+            0b010000110000011,
+            0b010111011011010,
+            0b010101111101101,
+        ],
+    ),
+    (
+        QrErrorCorrection::H,
+        [
+            0b001011010001001,
+            0b001001110111110,
+            0b001110011100111,
+            0b001100111010000,
+            0b000011101100010,
+            0b000001001010101,
+            0b000110100001100,
+            0b000100000111011,
+        ],
+    ),
+];
+
+/// Both copies of the format information, most significant bit first.
+fn format_information(matrix: &Matrix) -> (u32, u32) {
+    let size = matrix.size();
+    let bits = |modules: &mut dyn Iterator<Item = (usize, usize)>| {
+        modules.fold(0, |bits, (x, y)| bits << 1 | matrix.dark(x, y) as u32)
+    };
+
+    let first = bits(
+        &mut [0, 1, 2, 3, 4, 5, 7, 8]
+            .map(|x| (x, 8))
+            .into_iter()
+            .chain([7, 5, 4, 3, 2, 1, 0].map(|y| (8, y))),
+    );
+    let second = bits(
+        &mut (size - 7..size)
+            .rev()
+            .map(|y| (8, y))
+            .chain((size - 8..size).map(|x| (x, 8))),
+    );
+    (first, second)
+}
+
+fn rows(matrix: &Matrix) -> Vec<String> {
+    (0..matrix.size())
+        .map(|y| {
+            (0..matrix.size())
+                .map(|x| if matrix.dark(x, y) { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn format_information_matches_the_table() {
+    for (level, masks) in FORMAT {
+        let matrix = encode(b"thermal", level).unwrap();
+        let (first, second) = format_information(&matrix);
+        assert_eq!(first, second);
+        assert!(masks.contains(&first), "{level:?}: {first:015b}");
+    }
+}
+
+#[test]
+fn version_information() {
+    let matrix = encode(&[b'x'; 150], QrErrorCorrection::L).unwrap();
+    assert_eq!(matrix.size(), 45);
+
+    // Version 7 is the first to carry it, in two transposed 6x3 blocks
+    let expected = 0b000111_110010_010100;
+    for i in 0..18 {
+        let (a, b) = (matrix.size() - 11 + i % 3, i / 3);
+        let bit = (expected >> i) & 1 != 0;
+        assert_eq!(matrix.dark(a, b), bit, "bit {i}");
+        assert_eq!(matrix.dark(b, a), bit, "bit {i}");
+    }
+}
+
+#[test]
+fn byte_mode_symbols() {
+    let symbols = [
+        (
+            QrErrorCorrection::L,
+            [
+                "#######.....#.#######",
+                "#.....#...#.#.#.....#",
+                "#.###.#...###.#.###.#",
+                "#.###.#.#.#.#.#.###.#",
+                "#.###.#.##..#.#.###.#",
+                "#.....#..#.#..#.....#",
+                "#######.#.#.#.#######",
+                ".....................",
+                "##...###...#....##...",
+                "#.##.#....####..#....",
+                "...####.##..#.##..##.",
+                "#.##.#.##.#####..##..",
+                ".#.##.###..####..#..#",
+                "........#...#...#####",
+                "#######.#.##.#...###.",
+                "#.....#.#.....######.",
+                "#.###.#...##.#.#.#.#.",
+                "#.###.#..######.#.#..",
+                "#.###.#..#.###..##.##",
+                "#.....#.#.######.##..",
+                "#######.##..#...#..#.",
+            ],
+        ),
+        (
+            QrErrorCorrection::M,
+            [
+                "#######...###.#######",
+                "#.....#...#.#.#.....#",
+                "#.###.#.##..#.#.###.#",
+                "#.###.#.#.###.#.###.#",
+                "#.###.#.##..#.#.###.#",
+                "#.....#.#..#..#.....#",
+                "#######.#.#.#.#######",
+                "........###..........",
+                "#.#####..###..#####..",
+                ".##..#..##.#####....#",
+                ".#.######...#.##..##.",
+                ".#.###..##.#####.##..",
+                "###...##.##.#...#..#.",
+                "........###.#..######",
+                "#######..###.#...###.",
+                "#.....#.###......####",
+                "#.###.#.####.#.#.#.#.",
+                "#.###.#.#..######.#..",
+                "#.###.#.###.#.#......",
+                "#.....#..######..##..",
+                "#######.##..#...#..#.",
+            ],
+        ),
+        (
+            QrErrorCorrection::Q,
+            [
+                "#######.###.#.#######",
+                "#.....#..####.#.....#",
+                "#.###.#..#.##.#.###.#",
+                "#.###.#...##..#.###.#",
+                "#.###.#.#..#..#.###.#",
+                "#.....#.###...#.....#",
+                "#######.#.#.#.#######",
+                ".........####........",
+                ".#######...##..##...#",
+                ".......#####.###....#",
+                "#.##.##..#....##..##.",
+                "#..##..##..#####.##..",
+                "..#.#.#..#.#....#..#.",
+                "........##.....######",
+                "#######.#....#...###.",
+                "#.....#.####.....####",
+                "#.###.#.#.#.##.#.#.#.",
+                "#.###.#.##.######.#..",
+                "#.###.#.###.#.#......",
+                "#.....#.#.#####..##..",
+                "#######..#..#...#..#.",
+            ],
+        ),
+        (
+            QrErrorCorrection::H,
+            [
+                "#######.....#.#######",
+                "#.....#....##.#.....#",
+                "#.###.#.###...#.###.#",
+                "#.###.#.##..#.#.###.#",
+                "#.###.#....##.#.###.#",
+                "#.....#..#..#.#.....#",
+                "#######.#.#.#.#######",
+                "............#........",
+                "...##.##..#.#....##..",
+                "..#.#......#....#....",
+                "#..##.#...####.#.####",
+                ".#.##..###...#..#.#..",
+                ".##..####.#.##...#..#",
+                "........##..##..###..",
+                "#######.#....#..###..",
+                "#.....#..###..######.",
+                "#.###.#.#.#.#..#...##",
+                "#.###.#.#..####..##..",
+                "#.###.#..#.##.#.##.##",
+                "#.....#..#.#...#.####",
+                "#######....##........",
+            ],
+        ),
+    ];
+
+    for (level, expected) in symbols {
+        let matrix = encode(b"thermal", level).unwrap();
+        assert_eq!(rows(&matrix), expected, "{level:?}");
+    }
+}
@@ -0,0 +1,226 @@
+use std::{fs, path::Path};
+
+use thermal::{
+    commands::{
+        Command, CommandDiscriminants, DrawingFunction,
+        reader::{
+            Output,
+            state::{Mode, ParserState},
+        },
+    },
+    types::drawing::{DrawColor, DrawPoint, Line, LineStyle, Rectangle},
+};
+use winnow::{Partial, stream::StreamIsPartial};
+
+struct State(Mode);
+
+impl ParserState for State {
+    fn mode(&self) -> Mode {
+        self.0
+    }
+}
+
+/// Parses every command in `bytes`, writes it and checks that the written bytes parse back to
+/// an equal command. Returns how many commands were checked.
+fn roundtrip(bytes: &[u8], mode: Mode) -> usize {
+    let state = State(mode);
+    let mut input = Partial::new(bytes);
+    let _ = input.complete();
+    let mut checked = 0;
+
+    while !input.is_empty() {
+        let at = bytes.len() - input.len();
+        let command = match Command::parse(&mut input, &state) {
+            Ok(Output::Command(command)) => command,
+            Ok(_) => continue,
+            Err(e) => panic!("failed to parse {:02X?}: {e:?}", &bytes[at..]),
+        };
+
+        let mut written = vec![];
+        let len = command
+            .write_to(&mut written)
+            .unwrap_or_else(|e| panic!("failed to write {command:?}: {e}"));
+        assert_eq!(len, written.len());
+        assert!(
+            CommandDiscriminants::from_prefix(&written).contains(&(&command).into()),
+            "{command} wrote {written:02X?}, which doesn't start with its prefix"
+        );
+
+        let mut again = Partial::new(&written[..]);
+        let _ = again.complete();
+        match Command::parse(&mut again, &state) {
+            Ok(Output::Command(parsed)) => {
+                assert!(again.is_empty(), "{command} wrote trailing bytes");
+                assert_eq!(parsed, command, "at {at}");
+            }
+            other => panic!("{command} wrote {written:02X?}, which parses to {other:?}"),
+        }
+        checked += 1;
+    }
+
+    checked
+}
+
+/// One of each form the hand-written writer arms produce.
+const HAND_WRITTEN: &[&[u8]] = &[
+    b"\x0c",
+    b"\x18",
+    b"\x1b!\xb9",
+    b"\x1b&\x02\x41\x42\x02\xff\x00\x81\x18\x01\x02\x03",
+    b"\x1b(Y\x02\x00\x01\x00",
+    b"\x1b*\x21\x02\x00abcdef",
+    b"\x1b*\x00\x03\x00abc",
+    b"\x1b-\x02",
+    b"\x1bM\x01",
+    b"\x1bR\x05",
+    b"\x1bT\x03",
+    b"\x1bV\x02",
+    b"\x1bp\x01\x10\x20",
+    b"\x1br\x01",
+    b"\x1bu\x00",
+    b"\x10\x04\x01",
+    b"\x10\x04\x02",
+    b"\x10\x04\x03",
+    b"\x10\x04\x04",
+    b"\x10\x04\x07\x02",
+    b"\x10\x04\x08\x03",
+    b"\x10\x04\x12\x01",
+    b"\x10\x04\x12\x03",
+    b"\x10\x05\x02",
+    b"\x10\x14\x01\x01\x01",
+    b"\x10\x14\x01\x00\x08",
+    b"\x10\x14\x02\x01\x08",
+    b"\x10\x14\x08\x01\x03\x14\x01\x06\x02\x08",
+    b"\x1d!\x73",
+    b"\x1dV\x00",
+    b"\x1dV\x01",
+    b"\x1dVA\x05",
+    b"\x1dVb\x05",
+    b"\x1dVh\x02",
+    b"\x1dr\x04",
+];
+
+#[test]
+fn hand_written_commands() {
+    for mode in [Mode::Standard, Mode::Page] {
+        for bytes in HAND_WRITTEN {
+            assert_eq!(roundtrip(bytes, mode), 1, "{bytes:02X?}");
+        }
+    }
+}
+
+#[test]
+fn example_captures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "dat") {
+            continue;
+        }
+
+        let bytes = fs::read(&path).unwrap();
+        assert!(roundtrip(&bytes, Mode::Standard) > 0, "{}", path.display());
+    }
+}
+
+#[test]
+fn user_defined_character_at_end_of_range() {
+    assert_eq!(
+        roundtrip(b"\x1b&\x03\xff\xff\x01\xff\x00\xff", Mode::Standard),
+        1
+    );
+}
+
+/// Whether `bytes` parse as a single command, without panicking on them.
+fn parses(bytes: &[u8]) -> bool {
+    let mut input = Partial::new(bytes);
+    let _ = input.complete();
+    matches!(
+        Command::parse(&mut input, &State(Mode::Standard)),
+        Ok(Output::Command(_))
+    ) && input.is_empty()
+}
+
+#[test]
+fn truncated_commands() {
+    for bytes in [
+        &b"\x1b(Y\x02\x00\x01\x00"[..],
+        b"\x1bT\x03",
+        b"\x1bV\x02",
+        b"\x1br\x01",
+    ] {
+        assert!(parses(bytes), "{bytes:02X?}");
+        for len in 1..bytes.len() {
+            assert!(!parses(&bytes[..len]), "{:02X?}", &bytes[..len]);
+        }
+    }
+}
+
+#[test]
+fn out_of_range_parameters() {
+    // The bytes before and after the parameter, and the values it takes
+    let cases: [(&[u8], &[u8], &[u8]); 5] = [
+        (b"\x1b(Y\x02\x00", b"\x00", b"\x00\x01\x30\x31"),
+        (b"\x1b(Y\x02\x00\x00", b"", b"\x00\x01\x30\x31"),
+        (b"\x1bT", b"", b"\x00\x01\x02\x03\x30\x31\x32\x33"),
+        (b"\x1bV", b"", b"\x00\x01\x02\x30\x31\x32"),
+        (b"\x1br", b"", b"\x00\x01\x30\x31"),
+    ];
+
+    for (before, after, valid) in cases {
+        for n in 0..=255 {
+            let bytes = [before, &[n], after].concat();
+            assert_eq!(parses(&bytes), valid.contains(&n), "{bytes:02X?}");
+        }
+    }
+}
+
+/// Parses `bytes` as one command in Page mode, checking it writes back to the same bytes.
+fn page_mode_command(bytes: &[u8]) -> Command {
+    assert_eq!(roundtrip(bytes, Mode::Page), 1, "{bytes:02X?}");
+
+    let mut input = Partial::new(bytes);
+    let _ = input.complete();
+    let Ok(Output::Command(command)) = Command::parse(&mut input, &State(Mode::Page)) else {
+        panic!("{bytes:02X?} doesn't parse");
+    };
+
+    let mut written = vec![];
+    command.write_to(&mut written).unwrap();
+    assert_eq!(written, bytes);
+    command
+}
+
+#[test]
+fn draw_line() {
+    let command =
+        page_mode_command(b"\x1d(Q\x0c\x00\x30\x30\x10\x00\x20\x00\x80\x00\x20\x00\x02\x31");
+
+    assert_eq!(
+        command,
+        Command::Drawing(DrawingFunction::DrawLine(Line {
+            start: DrawPoint::new(16, 32),
+            end: DrawPoint::new(128, 32),
+            style: LineStyle::ModeratelyThick,
+            color: DrawColor::Color1,
+        }))
+    );
+}
+
+#[test]
+fn draw_rectangle() {
+    let command =
+        page_mode_command(b"\x1d(Q\x0d\x00\x30\x31\x10\x00\x20\x00\x80\x00\x40\x00\x61\x31\x32");
+
+    assert_eq!(
+        command,
+        Command::Drawing(DrawingFunction::DrawRectangle(Rectangle {
+            start: DrawPoint::new(16, 32),
+            end: DrawPoint::new(128, 64),
+            style: LineStyle::DoubleThin,
+            color: DrawColor::Color1,
+            fill: Some(DrawColor::Color2),
+        }))
+    );
+}
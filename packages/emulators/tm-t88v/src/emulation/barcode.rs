@@ -0,0 +1,33 @@
+use thermal::{
+    commands::{reader::Output, Command},
+    state::effect::barcode::Barcode,
+    types::font::Font,
+};
+
+use crate::{
+    device::TmT88v,
+    emulation::{error::Error, supported},
+};
+
+impl TmT88v {
+    /// The barcode's size and HRI settings followed by `GS k`. The settings aren't tracked in
+    /// the state, so all four are sent every time.
+    pub(super) fn apply_barcode(&mut self, barcode: Barcode) -> Result<Vec<Output>, Error> {
+        let data = barcode.encode().map_err(Error::InvalidBarcode)?;
+
+        if barcode.hri_font != Font::A && barcode.hri_font != Font::B {
+            return Err(Error::UnsupportedFont(barcode.hri_font));
+        }
+
+        [
+            Command::SetBarcodeHeight(barcode.height),
+            Command::SetBarcodeWidth(barcode.module_width),
+            Command::SelectPrintPositionOfHriCharacters(barcode.hri_position),
+            Command::SelectFontForHriCharacters(barcode.hri_font),
+            Command::PrintBarcode(data),
+        ]
+        .into_iter()
+        .map(supported)
+        .collect()
+    }
+}
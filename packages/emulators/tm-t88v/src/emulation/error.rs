@@ -1,9 +1,20 @@
 use miette::Diagnostic;
 use strum::Display;
-use thermal::types::font::Font;
+use thermal::{
+    commands::{validation::RangeError, CommandDiscriminants},
+    state::effect::barcode::BarcodeError,
+    types::font::Font,
+};
 
 #[derive(thiserror::Error, Diagnostic, Debug, Display)]
 pub enum Error {
     Unencodable(char),
     UnsupportedFont(Font),
+    /// The TM-T88V doesn't support the command.
+    Unsupported(CommandDiscriminants),
+    /// A command parameter is out of the range the reference allows.
+    OutOfRange(RangeError),
+    InvalidBarcode(BarcodeError),
+    /// Data of this many bytes doesn't fit in a symbol rendered in software.
+    SymbolTooLarge(usize),
 }
@@ -0,0 +1,161 @@
+use thermal::{
+    commands::{reader::Output, Command, GraphicsFunction},
+    state::{
+        effect::image::{Bitmap, Image, ImageEncoding},
+        LineSpacing, State,
+    },
+    types::{
+        bit_image_band::BitImageMode,
+        raster::{GraphicsData, RasterImage, RasterScale},
+    },
+};
+
+use crate::{
+    device::TmT88v,
+    emulation::{error::Error, supported},
+};
+
+/// Dots across the default print area on 80 mm paper.
+const PRINT_WIDTH: usize = 512;
+
+/// Most image data per command: what fits in a `GS ( L` block after its 10 header bytes.
+const MAX_CHUNK: usize = u16::MAX as usize - 10;
+
+/// Line spacing for `ESC *` bands: 24 dots at 180 dpi, in the 1/360 inch vertical motion unit.
+const BAND_SPACING: u8 = 48;
+
+impl TmT88v {
+    /// Dots across the print area, as `GS W` left it.
+    pub(super) fn printable_width(&self) -> usize {
+        self.state
+            .print_area_width()
+            .map_or(PRINT_WIDTH, usize::from)
+    }
+
+    /// `GS v 0` commands, each holding as many rows as fit in [`MAX_CHUNK`].
+    fn raster(bitmap: &Bitmap) -> Result<Vec<Output>, Error> {
+        let rows = (MAX_CHUNK / bitmap.row_bytes()).max(1);
+
+        (0..bitmap.height())
+            .step_by(rows)
+            .map(|top| {
+                let rows = rows.min(bitmap.height() - top);
+                supported(Command::PrintRasterBitImage(RasterImage::new(
+                    RasterScale::Normal,
+                    bitmap.row_bytes() as u16,
+                    rows as u16,
+                    bitmap.raster(top, rows),
+                )))
+            })
+            .collect()
+    }
+
+    /// `GS ( L` fn 112 and fn 50 for each [`MAX_CHUNK`] of rows.
+    fn graphics(bitmap: &Bitmap) -> Result<Vec<Output>, Error> {
+        let rows = (MAX_CHUNK / bitmap.row_bytes()).max(1);
+        let mut output = vec![];
+
+        for top in (0..bitmap.height()).step_by(rows) {
+            let rows = rows.min(bitmap.height() - top);
+            output.push(supported(Command::Graphics(
+                GraphicsFunction::StoreTheGraphicsDataInThePrintBuffer(GraphicsData::new(
+                    bitmap.width() as u16,
+                    rows as u16,
+                    bitmap.raster(top, rows),
+                )),
+            ))?);
+            output.push(supported(Command::Graphics(
+                GraphicsFunction::PrintTheGraphicsDataInThePrintBuffer,
+            ))?);
+        }
+
+        Ok(output)
+    }
+
+    /// `ESC *` 24-dot double-density bands, one per line with the line spacing set to the band
+    /// height and restored afterwards.
+    fn bit_image(&mut self, bitmap: &Bitmap) -> Result<Vec<Output>, Error> {
+        let previous = self.state.line_spacing().unwrap_or(LineSpacing::Default);
+        let mode = BitImageMode::Double24;
+
+        let mut output = self
+            .apply_state(&State::default().with_line_spacing(LineSpacing::Units(BAND_SPACING)))?;
+
+        for top in (0..bitmap.height()).step_by(mode.height_rows() as usize) {
+            output.push(supported(Command::SelectBitImageMode(
+                bitmap.band(top, mode),
+            ))?);
+            output.push(supported(Command::LineFeed)?);
+        }
+
+        output.extend(self.apply_state(&State::default().with_line_spacing(previous))?);
+
+        Ok(output)
+    }
+
+    pub(super) fn apply_image(&mut self, image: Image) -> Result<Vec<Output>, Error> {
+        let bitmap = image.to_bitmap(self.printable_width());
+
+        if bitmap.width() == 0 || bitmap.height() == 0 {
+            return Ok(vec![]);
+        }
+
+        match image.encoding.unwrap_or(ImageEncoding::Graphics) {
+            ImageEncoding::Raster => Self::raster(&bitmap),
+            ImageEncoding::Graphics => Self::graphics(&bitmap),
+            ImageEncoding::BitImage => self.bit_image(&bitmap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use thermal::{
+        commands::writer::encoder::to_bytes,
+        emulator::Emulator,
+        state::{
+            delta::Delta,
+            effect::image::{Dither, Image, ImageEncoding},
+        },
+    };
+
+    use crate::device::TmT88v;
+
+    /// A black image `width` dots wide and one dot high, as sent with `encoding`.
+    fn black_line(width: usize, encoding: ImageEncoding) -> Vec<u8> {
+        let image = Image::from_grayscale(width, 1, vec![0; width])
+            .with_dither(Dither::Threshold)
+            .with_encoding(encoding);
+
+        let outputs = TmT88v::default().apply(Delta::empty().with(image)).unwrap();
+        to_bytes(&outputs).unwrap()
+    }
+
+    #[test]
+    fn raster() {
+        assert_eq!(
+            black_line(9, ImageEncoding::Raster),
+            b"\x1dv0\x00\x02\x00\x01\x00\xff\x80"
+        );
+    }
+
+    #[test]
+    fn graphics() {
+        assert_eq!(
+            black_line(9, ImageEncoding::Graphics),
+            [
+                &b"\x1d(L\x0c\x000p0\x01\x01\x31\x09\x00\x01\x00\xff\x80"[..],
+                b"\x1d(L\x02\x000\x32",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn bit_image_restores_the_line_spacing() {
+        assert_eq!(
+            black_line(2, ImageEncoding::BitImage),
+            b"\x1b3\x30\x1b*\x21\x02\x00\x80\x00\x00\x80\x00\x00\n\x1b2"
+        );
+    }
+}
@@ -1,26 +1,55 @@
+pub mod barcode;
 pub mod error;
 pub mod feed_and_cut;
+pub mod image;
+pub mod pulse;
+pub mod rule;
+pub mod state;
+pub mod symbol;
 pub mod write;
 
 use facet_pretty::FacetPretty;
 use thermal::{
-    commands::{reader::Output, Command},
+    commands::{reader::Output, Command, Model},
     emulator::Emulator,
     state::{
         delta::Delta,
         effect::{self, Effect},
     },
-    types::font::Font,
 };
 
 use crate::{device::TmT88v, emulation};
 
+/// `command` as output, or an error if the TM-T88V doesn't support it or its parameters are
+/// out of range. Commands the spec has no model data for are let through, since the emulator
+/// only emits commands it was written for.
+fn supported(command: Command) -> Result<Output, emulation::error::Error> {
+    if command.supported_on(Model::TmT88v) == Some(false) {
+        return Err(emulation::error::Error::Unsupported((&command).into()));
+    }
+
+    command
+        .validate()
+        .map_err(emulation::error::Error::OutOfRange)?;
+
+    Ok(Output::Command(command))
+}
+
 impl TmT88v {
     fn apply_single(&mut self, effect: Effect) -> Result<Vec<Output>, emulation::error::Error> {
         match effect {
             Effect::Write(write) => self.apply_write(write),
             Effect::Feed(feed) => self.apply_feed(feed),
             Effect::Cut(cut) => self.apply_cut(cut),
+            Effect::Image(image) => self.apply_image(image),
+            Effect::Barcode(barcode) => self.apply_barcode(barcode),
+            Effect::QrCode(qr) => self.apply_qr_code(qr),
+            Effect::Pdf417(pdf417) => self.apply_pdf417(pdf417),
+            Effect::DataMatrix(matrix) => self.apply_data_matrix(matrix),
+            Effect::Rule(rule) => self.apply_rule(rule),
+            Effect::OpenDrawer(drawer) => self.apply_open_drawer(drawer),
+            Effect::Beep(beep) => self.apply_beep(beep),
+            Effect::SetState(state) => self.apply_state(&state),
         }
     }
 }
@@ -45,41 +74,6 @@ impl Emulator for TmT88v {
             collection.extend_from_slice(&self.apply_single(effect.clone())?);
         }
 
-        if let Some(font) = delta.apply_font {
-            if font != Font::A && font != Font::B {
-                return Err(emulation::error::Error::UnsupportedFont(font));
-            }
-
-            self.state = self.state.clone().with_font(font);
-            collection.push(Output::Command(Command::SelectCharacterFont(font)));
-        }
-
-        if let Some(justification) = delta.apply_justification {
-            self.state = self.state.clone().with_justification(justification);
-            collection.push(Output::Command(Command::SelectJustification(justification)))
-        }
-
-        if let Some(ref ascii_variant) = delta.apply_ascii_variant {
-            self.state = self.state.clone().with_ascii_variant(*ascii_variant);
-            collection.push(Output::Command(Command::SelectInternationalCharacterSet(
-                *ascii_variant,
-            )))
-        }
-
-        if let Some(ref codepage) = delta.apply_codepage {
-            self.state = self.state.clone().with_codepage(*codepage);
-            collection.push(Output::Command(Command::SelectCharacterCodeTable(
-                *codepage,
-            )))
-        }
-
-        if let Some(ref scale) = delta.apply_text_scale {
-            self.state = self.state.clone().with_text_scale(*scale);
-            collection.push(Output::Command(Command::SelectCharacterSize(
-                scale.0, scale.1,
-            )))
-        }
-
         Ok(collection)
     }
 
@@ -0,0 +1,49 @@
+use thermal::{
+    commands::{reader::Output, Command, CommandDiscriminants},
+    state::effect::pulse::{Beep, OpenDrawer},
+};
+
+use crate::{
+    device::TmT88v,
+    emulation::{error::Error, supported},
+};
+
+impl TmT88v {
+    pub(super) fn apply_open_drawer(&mut self, drawer: OpenDrawer) -> Result<Vec<Output>, Error> {
+        let (on, off) = drawer.pulse_units();
+
+        Ok(vec![supported(Command::GeneratePulse(
+            drawer.connector,
+            on,
+            off,
+        ))?])
+    }
+
+    /// The TM-T88V has no beeper for `ESC ( A` to sound. An external buzzer would hang off
+    /// the drawer kick-out connector, where a pulse can just as well open a drawer, so beeps
+    /// aren't sent at all.
+    pub(super) fn apply_beep(&mut self, _beep: Beep) -> Result<Vec<Output>, Error> {
+        Err(Error::Unsupported(CommandDiscriminants::Beeper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use thermal::{
+        commands::CommandDiscriminants,
+        emulator::Emulator,
+        state::{delta::Delta, effect::pulse::Beep},
+    };
+
+    use crate::{device::TmT88v, emulation::error::Error};
+
+    #[test]
+    fn beep_is_unsupported() {
+        let delta = Delta::empty().with(Beep::new(2));
+
+        assert!(matches!(
+            TmT88v::default().apply(delta),
+            Err(Error::Unsupported(CommandDiscriminants::Beeper))
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+use thermal::{commands::reader::Output, state::effect::rule::Rule};
+
+use crate::{device::TmT88v, emulation::error::Error};
+
+impl TmT88v {
+    /// The rule as an image across the print area. Models with `GS ( Q` could draw solid and
+    /// double rules in page mode, but the TM-T88V doesn't have it.
+    pub(super) fn apply_rule(&mut self, rule: Rule) -> Result<Vec<Output>, Error> {
+        self.apply_image(rule.to_image(self.printable_width()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use thermal::{
+        commands::{reader::Output, Command, GraphicsFunction},
+        emulator::Emulator,
+        state::{delta::Delta, effect::rule::Rule},
+    };
+
+    use crate::device::TmT88v;
+
+    #[test]
+    fn rule_is_graphics_across_the_print_area() {
+        let outputs = TmT88v::default()
+            .apply(Delta::empty().with(Rule::new()))
+            .unwrap();
+
+        let [Output::Command(Command::Graphics(
+            GraphicsFunction::StoreTheGraphicsDataInThePrintBuffer(data),
+        )), Output::Command(Command::Graphics(
+            GraphicsFunction::PrintTheGraphicsDataInThePrintBuffer,
+        ))] = &outputs[..]
+        else {
+            panic!("{outputs:?}");
+        };
+        assert_eq!((data.width, data.height), (512, 2));
+        assert!(data.data.iter().all(|b| *b == 0xFF));
+    }
+}
@@ -0,0 +1,239 @@
+use thermal::{
+    commands::{
+        reader::{state::Mode, Output},
+        Command,
+    },
+    state::{LineSpacing, State},
+    types::{basic_styles::BasicStyles, font::Font},
+};
+
+use crate::{
+    device::TmT88v,
+    emulation::{error::Error, supported},
+};
+
+impl TmT88v {
+    /// `ESC !` setting the font, emphasis, underline and character size `changes` leave the
+    /// printer with, when it can express all four and stands in for more than one command.
+    fn print_mode(&self, changes: &State) -> Option<BasicStyles> {
+        let count = [
+            changes.font().is_some(),
+            changes.emphasized().is_some(),
+            changes.underline().is_some(),
+            changes.text_scale().is_some(),
+        ]
+        .into_iter()
+        .filter(|c| *c)
+        .count();
+        if count < 2 {
+            return None;
+        }
+
+        let font_index = match changes.font().or(self.state.font())? {
+            Font::A => 0,
+            Font::B => 1,
+            _ => return None,
+        };
+        let emphasized = changes.emphasized().or(self.state.emphasized())?;
+        let underline = changes.underline().or(self.state.underline())?;
+        let (width, height) = changes.text_scale().or(self.state.text_scale())?;
+        if underline > 1 || !(1..=2).contains(&width) || !(1..=2).contains(&height) {
+            return None;
+        }
+
+        Some(
+            BasicStyles::new()
+                .with_font_index(font_index)
+                .with_emphasized(emphasized)
+                .with_underline(underline == 1)
+                .with_double_width(width == 2)
+                .with_double_height(height == 2),
+        )
+    }
+
+    /// The commands switching the printer to the modes `into` sets, skipping the ones it's
+    /// already in, in the order the printer needs them: the mode and the print area before the
+    /// character settings.
+    pub(super) fn apply_state(&mut self, into: &State) -> Result<Vec<Output>, Error> {
+        let changes = self.state.changes(into);
+        let mut commands = vec![];
+        let mut emit = |command: Command| {
+            commands.push(supported(command)?);
+            Ok(())
+        };
+
+        if let Some(font) = changes.font() {
+            if font != Font::A && font != Font::B {
+                return Err(Error::UnsupportedFont(font));
+            }
+        }
+
+        let print_mode = self.print_mode(&changes);
+        // Only kept once every command is accepted, so a rejected one leaves the state as it was
+        let mut state = self.state.clone();
+
+        if let Some(mode) = changes.mode() {
+            state = state.with_mode(mode);
+            emit(match mode {
+                Mode::Standard => Command::SelectStandardMode,
+                Mode::Page => Command::SelectPageMode,
+            })?;
+        }
+
+        if let Some(margin) = changes.left_margin() {
+            state = state.with_left_margin(margin);
+            emit(Command::SetLeftMargin(margin))?;
+        }
+
+        if let Some(width) = changes.print_area_width() {
+            state = state.with_print_area_width(width);
+            emit(Command::SetPrintAreaWidth(width))?;
+        }
+
+        if let Some(styles) = print_mode {
+            let (width, height) = (
+                1 + styles.double_width() as u8,
+                1 + styles.double_height() as u8,
+            );
+            state = state
+                .with_font(if styles.font_index() == 0 {
+                    Font::A
+                } else {
+                    Font::B
+                })
+                .with_emphasized(styles.emphasized())
+                .with_underline(styles.underline() as u8)
+                .with_text_scale((width, height));
+            emit(Command::SelectPrintMode(styles))?;
+        } else {
+            if let Some(font) = changes.font() {
+                state = state.with_font(font);
+                emit(Command::SelectCharacterFont(font))?;
+            }
+
+            if let Some(emphasized) = changes.emphasized() {
+                state = state.with_emphasized(emphasized);
+                emit(Command::TurnEmphasizedModeOnOff(emphasized))?;
+            }
+
+            if let Some(underline) = changes.underline() {
+                state = state.with_underline(underline);
+                emit(Command::TurnUnderlineModeOnOff(underline))?;
+            }
+
+            if let Some(scale) = changes.text_scale() {
+                state = state.with_text_scale(scale);
+                emit(Command::SelectCharacterSize(scale.0, scale.1))?;
+            }
+        }
+
+        if let Some(justification) = changes.justification() {
+            state = state.with_justification(justification);
+            emit(Command::SelectJustification(justification))?;
+        }
+
+        if let Some(ascii_variant) = changes.ascii_variant() {
+            state = state.with_ascii_variant(ascii_variant);
+            emit(Command::SelectInternationalCharacterSet(ascii_variant))?;
+        }
+
+        if let Some(codepage) = changes.codepage() {
+            state = state.with_codepage(codepage);
+            emit(Command::SelectCharacterCodeTable(codepage))?;
+        }
+
+        if let Some(double_strike) = changes.double_strike() {
+            state = state.with_double_strike(double_strike);
+            emit(Command::TurnDoubleStrikeModeOnOff(double_strike))?;
+        }
+
+        if let Some(reverse) = changes.reverse() {
+            state = state.with_reverse(reverse);
+            emit(Command::TurnWhiteBlackReversePrintModeOnOff(reverse))?;
+        }
+
+        if let Some(upside_down) = changes.upside_down() {
+            state = state.with_upside_down(upside_down);
+            emit(Command::TurnUpsideDownPrintModeOnOff(upside_down))?;
+        }
+
+        if let Some(rotation) = changes.rotation() {
+            state = state.with_rotation(rotation);
+            emit(Command::Turn90ClockwiseRotationModeOnOff(rotation))?;
+        }
+
+        if let Some(spacing) = changes.line_spacing() {
+            state = state.with_line_spacing(spacing);
+            emit(match spacing {
+                LineSpacing::Default => Command::SelectDefaultLineSpacing,
+                LineSpacing::Units(n) => Command::SetLineSpacing(n),
+            })?;
+        }
+
+        if let Some(spacing) = changes.right_spacing() {
+            state = state.with_right_spacing(spacing);
+            emit(Command::SetRightSideCharacterSpacing(spacing))?;
+        }
+
+        if let Some(color) = changes.print_color() {
+            state = state.with_print_color(color);
+            emit(Command::SelectPrintColor(color))?;
+        }
+
+        if let Some(enabled) = changes.user_defined_characters() {
+            state = state.with_user_defined_characters(enabled);
+            emit(Command::SelectCancelUserDefinedCharacterSet(enabled))?;
+        }
+
+        self.state = state;
+        Ok(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use thermal::{
+        commands::writer::encoder::to_bytes,
+        emulator::Emulator,
+        state::{delta::Delta, effect::print::Write, State},
+        types::{font::Font, justification::Justification},
+    };
+
+    use crate::device::TmT88v;
+
+    #[test]
+    fn only_changes_are_emitted() {
+        let delta = Delta::empty()
+            .with(State::default().with_emphasized(true))
+            .with(Write::from_str("a"))
+            .with(
+                State::default()
+                    .with_emphasized(true)
+                    .with_justification(Justification::Centered),
+            )
+            .with(Write::from_str("b").with_justification(Justification::Centered))
+            .with(Write::from_str("c").with_font(Font::B).with_scale(2, 2));
+
+        let outputs = TmT88v::default().apply(delta).unwrap();
+        let bytes = to_bytes(&outputs).unwrap();
+        assert_eq!(
+            bytes,
+            [
+                &b"\x1bE\x01\x1bR\x00a"[..],
+                b"\x1ba\x01b",
+                b"\x1bM\x01\x1d!\x11c",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn rejected_commands_leave_the_state() {
+        let mut printer = TmT88v::default();
+        let underline = || Delta::empty().with(State::default().with_underline(5));
+
+        assert!(printer.apply(underline()).is_err());
+        assert!(printer.apply(underline()).is_err());
+        assert_eq!(printer.state, State::default());
+    }
+}
@@ -0,0 +1,95 @@
+use thermal::{
+    commands::{reader::Output, Command, Model, SymbolFunction},
+    state::{
+        effect::symbol::{DataMatrix, Fallback, Pdf417, QrCode},
+        State,
+    },
+    types::{
+        justification::Justification,
+        symbol::{QrModel, SymbolData},
+    },
+};
+
+use crate::{
+    device::TmT88v,
+    emulation::{error::Error, supported},
+};
+
+impl TmT88v {
+    /// `ESC a` if needed, then the symbol's `GS ( k` settings, store and print functions. The
+    /// settings aren't tracked in the state, so all of them are sent every time.
+    fn symbol(
+        &mut self,
+        justification: Justification,
+        functions: Vec<SymbolFunction>,
+    ) -> Result<Vec<Output>, Error> {
+        let mut output = self.apply_state(&State::default().with_justification(justification))?;
+
+        for function in functions {
+            output.push(supported(Command::Symbol(function))?);
+        }
+
+        Ok(output)
+    }
+
+    /// `GS ( k` QR Code functions, or the symbol rendered as an image when its fallback asks
+    /// for it.
+    pub(super) fn apply_qr_code(&mut self, qr: QrCode) -> Result<Vec<Output>, Error> {
+        let functions = vec![
+            SymbolFunction::QrSelectModel(QrModel::Model2),
+            SymbolFunction::QrSetModuleSize(qr.module_size),
+            SymbolFunction::QrSetErrorCorrection(qr.error_correction),
+            SymbolFunction::QrStoreData(SymbolData::new(qr.data.clone())),
+            SymbolFunction::QrPrint,
+        ];
+
+        let render = match qr.fallback {
+            Fallback::Never => false,
+            Fallback::WhenUnsupported => functions
+                .iter()
+                .any(|f| Command::Symbol(f.clone()).supported_on(Model::TmT88v) == Some(false)),
+            Fallback::Always => true,
+        };
+        if !render {
+            return self.symbol(qr.justification, functions);
+        }
+
+        let image = qr
+            .to_image(self.printable_width())
+            .ok_or(Error::SymbolTooLarge(qr.data.len()))?;
+        let mut output =
+            self.apply_state(&State::default().with_justification(qr.justification))?;
+        output.extend(self.apply_image(image)?);
+
+        Ok(output)
+    }
+
+    pub(super) fn apply_pdf417(&mut self, pdf417: Pdf417) -> Result<Vec<Output>, Error> {
+        self.symbol(
+            pdf417.justification,
+            vec![
+                SymbolFunction::Pdf417SetColumns(pdf417.columns),
+                SymbolFunction::Pdf417SetRows(pdf417.rows),
+                SymbolFunction::Pdf417SetModuleWidth(pdf417.module_width),
+                SymbolFunction::Pdf417SetRowHeight(pdf417.row_height),
+                SymbolFunction::Pdf417SetErrorCorrection(pdf417.error_correction),
+                SymbolFunction::Pdf417SelectOptions(pdf417.truncated as u8),
+                SymbolFunction::Pdf417StoreData(SymbolData::new(pdf417.data)),
+                SymbolFunction::Pdf417Print,
+            ],
+        )
+    }
+
+    /// `GS ( k` DataMatrix functions, which the TM-T88V doesn't have.
+    pub(super) fn apply_data_matrix(&mut self, matrix: DataMatrix) -> Result<Vec<Output>, Error> {
+        self.symbol(
+            matrix.justification,
+            vec![
+                SymbolFunction::DataMatrixSetSymbolType(matrix.symbol_type),
+                SymbolFunction::DataMatrixSetModuleSize(matrix.module_size),
+                SymbolFunction::DataMatrixStoreData(SymbolData::new(matrix.data)),
+                SymbolFunction::DataMatrixPrint,
+            ],
+        )
+    }
+}
@@ -1,13 +1,17 @@
+use std::sync::LazyLock;
+
 use itertools::chain;
+use strum::IntoEnumIterator;
 use thermal::{
-    commands::{reader::Output, Command},
-    emulator::Emulator,
+    commands::{reader::Output, Command, Model},
     state::{
-        delta::Delta,
         effect::print::{Write, WriteContents},
         IntoState, State,
     },
-    types::character_set::{AsciiVariant, Codepage},
+    types::{
+        character_set::{AsciiVariant, Codepage},
+        print_color::PrintColor,
+    },
 };
 use thermal_encoding::encoding::PartialUnicodeEncoding;
 
@@ -20,109 +24,101 @@ trait UnicodeIntoState<T>: PartialUnicodeEncoding<T> + IntoState {}
 
 impl<V, T: PartialUnicodeEncoding<V> + IntoState> UnicodeIntoState<V> for T {}
 
-const SUPPORTED_ASCII_VARIANTS: [&AsciiVariant; 18] = [
-    &AsciiVariant::Usa,
-    &AsciiVariant::France,
-    &AsciiVariant::Germany,
-    &AsciiVariant::Uk,
-    &AsciiVariant::Denmark1,
-    &AsciiVariant::Sweden,
-    &AsciiVariant::Italy,
-    &AsciiVariant::Spain1,
-    &AsciiVariant::Japan,
-    &AsciiVariant::Norway,
-    &AsciiVariant::Denmark2,
-    &AsciiVariant::Spain2,
-    &AsciiVariant::LatinAmerica,
-    &AsciiVariant::Korea,
-    &AsciiVariant::SloveniaCroatia,
-    &AsciiVariant::China,
-    &AsciiVariant::Vietnam,
-    &AsciiVariant::Arabia,
-];
-
-const SUPPORTED_CODEPAGES: [&Codepage; 15] = [
-    &Codepage::Page0_Pc437,
-    &Codepage::Page1_Katakana,
-    &Codepage::Page2_Pc850,
-    &Codepage::Page3_Pc860,
-    &Codepage::Page4_Pc863,
-    &Codepage::Page5_Pc865,
-    // Doesn't support a bunch of JP tables
-    &Codepage::Page11_Pc851,
-    &Codepage::Page12_Pc853,
-    &Codepage::Page13_Pc857,
-    &Codepage::Page14_Pc737,
-    &Codepage::Page15_Iso8859_7,
-    &Codepage::Page16_Wpc1252,
-    &Codepage::Page17_Pc866,
-    &Codepage::Page18_Pc852,
-    &Codepage::Page19_Pc858,
-    // TODO: &Codepage::Page20_Thai42
-    // Doesn't support a bunch of Thai
-    // TODO: Codepages 26..=?
-];
+/// International character sets the TM-T88V can select with `ESC R`, from the spec's model data.
+static SUPPORTED_ASCII_VARIANTS: LazyLock<Vec<AsciiVariant>> = LazyLock::new(|| {
+    AsciiVariant::iter()
+        .filter(|v| {
+            Command::SelectInternationalCharacterSet(*v).supported_on(Model::TmT88v) == Some(true)
+        })
+        .collect()
+});
+
+/// Code tables the TM-T88V can select with `ESC t`, from the spec's model data.
+static SUPPORTED_CODEPAGES: LazyLock<Vec<Codepage>> = LazyLock::new(|| {
+    Codepage::iter()
+        .filter(|c| Command::SelectCharacterCodeTable(*c).supported_on(Model::TmT88v) == Some(true))
+        .collect()
+});
 
 impl TmT88v {
+    /// `string` in the character sets that can encode it, switching between them as needed.
+    fn encode_text(&mut self, string: &str) -> Result<Vec<Output>, Error> {
+        let v = self.state.ascii_variant();
+        let c = self.state.codepage();
+
+        Ok(thermal_encoding::encode_str(
+            string,
+            chain!(
+                v.iter().map(|v| v as &dyn UnicodeIntoState<
+                    Result<Vec<Output>, emulation::error::Error>,
+                >),
+                c.iter().map(|v| v as &dyn UnicodeIntoState<
+                    Result<Vec<Output>, emulation::error::Error>,
+                >),
+                SUPPORTED_ASCII_VARIANTS
+                    .iter()
+                    .map(|v| v as &dyn UnicodeIntoState<
+                        Result<Vec<Output>, emulation::error::Error>,
+                    >),
+                SUPPORTED_CODEPAGES
+                    .iter()
+                    .map(|v| v as &dyn UnicodeIntoState<
+                        Result<Vec<Output>, emulation::error::Error>,
+                    >),
+            ),
+            |a, b| {
+                self.apply_state(&b.into_state()).map(|mut v| {
+                    v.extend(a.into_iter().map(|v| Output::Raw(*v)));
+                    v
+                })
+            },
+        )
+        .map(|rr| rr.map_err(|c| Error::Unencodable(c)).and_then(|r| r))
+        .collect::<Result<Vec<Vec<Output>>, Error>>()?
+        .into_iter()
+        .flatten()
+        .collect())
+    }
+
     pub(super) fn apply_write(&mut self, write: Write) -> Result<Vec<Output>, Error> {
-        let mut commands = self.apply(
-            self.state.delta(write.font.into_state())
-                + self.state.delta(write.justification.into_state())
-                + self
-                    .state
-                    .delta(State::default().with_text_scale(write.scale)),
-        )?;
+        let mut commands = self.apply_state(&write.state())?;
+        let scale = self.state.text_scale().unwrap_or((1, 1));
 
         match write.contents {
-            WriteContents::Utf8(string) => {
-                let v = self.state.ascii_variant();
-                let c = self.state.codepage();
-
-                let i = thermal_encoding::encode_str(
-                    &string,
-                    chain!(
-                        v.iter().map(|v| v as &dyn UnicodeIntoState<
-                            Result<Vec<Output>, emulation::error::Error>,
-                        >),
-                        c.iter().map(|v| v as &dyn UnicodeIntoState<
-                            Result<Vec<Output>, emulation::error::Error>,
-                        >),
-                        SUPPORTED_ASCII_VARIANTS.iter().copied().map(|v| v
-                            as &dyn UnicodeIntoState<Result<Vec<Output>, emulation::error::Error>>),
-                        SUPPORTED_CODEPAGES
-                            .iter()
-                            .copied()
-                            .map(|v| v as &dyn UnicodeIntoState<
-                                Result<Vec<Output>, emulation::error::Error>,
-                            >),
-                    ),
-                    |a, b| {
-                        let delta = self.state.delta(b.into_state());
-
-                        self.apply(delta).map(|mut v| {
-                            v.extend(a.into_iter().map(|v| Output::Raw(*v)));
-                            v
-                        })
-                    },
-                )
-                .map(|rr| rr.map_err(|c| Error::Unencodable(c)).and_then(|r| r))
-                .collect::<Result<Vec<Vec<Output>>, Error>>()?
-                .into_iter()
-                .flatten();
-
-                commands.extend(i);
+            WriteContents::Utf8(string) => commands.extend(self.encode_text(&string)?),
+
+            WriteContents::Rich(spans) => {
+                // The modes to switch back to: as they were, or the printer's defaults
+                let restore = State::default()
+                    .with_emphasized(self.state.emphasized().unwrap_or(false))
+                    .with_underline(self.state.underline().unwrap_or(0))
+                    .with_reverse(self.state.reverse().unwrap_or(false))
+                    .with_text_scale(scale)
+                    .with_print_color(self.state.print_color().unwrap_or(PrintColor::Black));
+
+                for span in spans {
+                    commands.extend(self.apply_state(&span.state(scale))?);
+                    commands.extend(self.encode_text(&span.text)?);
+                }
+
+                commands.extend(self.apply_state(&restore)?);
             }
 
             WriteContents::AsciiLike(data, variant, codepage) => {
                 let variant_used = data.iter().any(|v| *v < 128);
                 let codepage_used = data.iter().any(|v| *v >= 128);
 
-                let mut delta = Delta::empty();
+                let mut state = State::default();
+
+                if variant_used {
+                    state = state.with_ascii_variant(variant);
+                }
 
-                delta.apply_ascii_variant = variant_used.then_some(variant);
-                delta.apply_codepage = codepage_used.then_some(codepage);
+                if codepage_used {
+                    state = state.with_codepage(codepage);
+                }
 
-                commands.extend_from_slice(&self.apply(delta)?);
+                commands.extend_from_slice(&self.apply_state(&state)?);
 
                 for byte in data {
                     commands.push(Output::Raw(byte));
@@ -133,3 +129,47 @@ impl TmT88v {
         Ok(commands)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use thermal::{
+        commands::writer::encoder::to_bytes,
+        emulator::Emulator,
+        state::{
+            delta::Delta,
+            effect::print::{Span, Write},
+            State,
+        },
+        types::justification::Justification,
+    };
+
+    use crate::device::TmT88v;
+
+    #[test]
+    fn state_between_writes_stays_in_effect() {
+        let delta = Delta::empty()
+            .with(Write::from_str("a"))
+            .with(State::default().with_justification(Justification::Centered))
+            .with(Write::from_str("b"));
+
+        let outputs = TmT88v::default().apply(delta).unwrap();
+        let bytes = to_bytes(&outputs).unwrap();
+        assert!(bytes.ends_with(b"a\x1ba\x01b"), "{bytes:?}");
+    }
+
+    #[test]
+    fn rich_spans_switch_only_what_changes_and_switch_back() {
+        let delta = Delta::empty().with(Write::rich([
+            Span::new("a").with_emphasized(true),
+            Span::new("b").with_emphasized(true).with_underline(1),
+            Span::new("c").with_underline(1),
+        ]));
+
+        let outputs = TmT88v::default().apply(delta).unwrap();
+        let bytes = to_bytes(&outputs).unwrap();
+        assert!(
+            bytes.ends_with(b"a\x1b-\x01b\x1bE\x00c\x1b-\x00"),
+            "{bytes:?}"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use syn::Ident;
+
+/// Notation of a command as given in the "[Format]" section of its page, one entry per line.
+#[derive(Deserialize)]
+pub struct Codes {
+    #[serde(default)]
+    pub ascii: Option<serde_json::Value>,
+    #[serde(default)]
+    pub hex: Option<serde_json::Value>,
+    #[serde(default)]
+    pub dec: Option<serde_json::Value>,
+}
+
+fn lines(v: &serde_json::Value) -> Vec<String> {
+    match v {
+        serde_json::Value::String(s) => vec![s.clone()],
+        serde_json::Value::Array(arr) => arr
+            .iter()
+            .filter_map(|x| x.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Control characters by the names the ASCII notation uses for them.
+const CONTROL_NAMES: [&str; 32] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FS", "GS", "RS", "US",
+];
+
+/// The bytes of a hex line before its first parameter.
+fn hex_prefix(line: &str) -> Vec<u8> {
+    line.split_whitespace()
+        .map_while(|tok| u8::from_str_radix(tok, 16).ok().filter(|_| tok.len() == 2))
+        .collect()
+}
+
+/// Whether an ASCII notation token stands for `byte`. A number may be the byte's value or its
+/// character: the `2` of `ESC 2` is 0x32, but the `1` of `DLE DC4 1` is 0x01.
+fn names_byte(token: &str, byte: u8) -> bool {
+    CONTROL_NAMES.get(usize::from(byte)) == Some(&token)
+        || (token == "SP" && byte == b' ')
+        || token.parse() == Ok(byte)
+        || token.chars().eq([char::from(byte)])
+}
+
+impl Codes {
+    pub fn ascii_lines(&self) -> Vec<String> {
+        self.ascii.as_ref().map(lines).unwrap_or_default()
+    }
+
+    /// Checks that the ASCII, hex and decimal notations give the same bytes before the first
+    /// parameter of each line.
+    ///
+    /// The codes are overrides copied from the pages, and escpos_build can only compare them
+    /// with the pages it parsed a "[Format]" section from, so the three notations are checked
+    /// against each other here.
+    pub fn validate(&self, id: &str) -> Result<(), String> {
+        let hex = self.hex.as_ref().map(lines).unwrap_or_default();
+        let dec = self.dec.as_ref().map(lines).unwrap_or_default();
+        let ascii = self.ascii_lines();
+
+        for (name, other) in [("ASCII", &ascii), ("decimal", &dec)] {
+            if !other.is_empty() && other.len() != hex.len() {
+                return Err(format!(
+                    "Codes of '{id}' have {} {name} lines but {} hex lines",
+                    other.len(),
+                    hex.len()
+                ));
+            }
+        }
+
+        for (i, line) in hex.iter().enumerate() {
+            let prefix = hex_prefix(line);
+
+            if let Some(dec) = dec.get(i) {
+                let bytes: Vec<u8> = dec
+                    .split_whitespace()
+                    .take(prefix.len())
+                    .map_while(|tok| tok.parse().ok())
+                    .collect();
+                if bytes != prefix {
+                    return Err(format!("Codes of '{id}': `{dec}` doesn't match `{line}`"));
+                }
+            }
+
+            if let Some(ascii) = ascii.get(i) {
+                let tokens: Vec<&str> = ascii.split_whitespace().take(prefix.len()).collect();
+                if tokens.len() != prefix.len()
+                    || !tokens.iter().zip(&prefix).all(|(t, b)| names_byte(t, *b))
+                {
+                    return Err(format!("Codes of '{id}': `{ascii}` doesn't match `{line}`"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bytes shared by every hex line before the first parameter, e.g. `1B 4A n` gives `[0x1B, 0x4A]`.
+    pub fn prefix(&self) -> Option<Vec<u8>> {
+        let prefixes: Vec<Vec<u8>> = self
+            .hex
+            .as_ref()
+            .map(lines)
+            .unwrap_or_default()
+            .iter()
+            .map(|line| hex_prefix(line))
+            .collect();
+
+        let (first, rest) = prefixes.split_first()?;
+        let len = rest.iter().fold(first.len(), |len, p| {
+            first
+                .iter()
+                .zip(p)
+                .take(len)
+                .take_while(|(a, b)| a == b)
+                .count()
+        });
+
+        (len > 0).then(|| first[..len].to_vec())
+    }
+}
+
+/// What the generated lookups need to know about one variant.
+pub struct Format<'a> {
+    pub variant: &'a Ident,
+    pub has_fields: bool,
+    pub prefix: Vec<u8>,
+    pub ascii: Vec<String>,
+}
+
+/// Generates `prefix_bytes()` on the command enum and `prefix_bytes()`, `ascii_format()` and
+/// `from_prefix()` on its discriminants.
+pub fn format_tokens(enum_name: &Ident, formats: &[Format<'_>]) -> TokenStream2 {
+    let discr_name = format_ident!("{}Discriminants", enum_name);
+
+    let prefix_arms = formats.iter().map(|f| {
+        let v = f.variant;
+        let prefix = &f.prefix;
+        quote!( Self::#v => &[#(#prefix),*], )
+    });
+
+    let ascii_arms = formats.iter().map(|f| {
+        let v = f.variant;
+        let ascii = &f.ascii;
+        quote!( Self::#v => &[#(#ascii),*], )
+    });
+
+    let command_arms = formats.iter().map(|f| {
+        let v = f.variant;
+        if f.has_fields {
+            quote!( Self::#v(..) => #discr_name::#v.prefix_bytes(), )
+        } else {
+            quote!( Self::#v => #discr_name::#v.prefix_bytes(), )
+        }
+    });
+
+    // Group variants sharing a prefix (e.g. every `GS ( k` function), longest prefix first so
+    // that the first hit in the table is the most specific one.
+    let mut groups: Vec<(&[u8], Vec<&Ident>)> = Vec::new();
+    for f in formats.iter().filter(|f| !f.prefix.is_empty()) {
+        match groups.iter_mut().find(|(p, _)| *p == &f.prefix[..]) {
+            Some((_, variants)) => variants.push(f.variant),
+            None => groups.push((&f.prefix, vec![f.variant])),
+        }
+    }
+    groups.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+
+    let table = groups
+        .iter()
+        .map(|(prefix, variants)| quote!( (&[#(#prefix),*], &[#(#discr_name::#variants),*]), ));
+
+    quote! {
+        impl #enum_name {
+            /// Bytes that introduce this command on the wire, before any parameter.
+            pub fn prefix_bytes(&self) -> &'static [u8] {
+                match self {
+                    #(#command_arms)*
+                }
+            }
+        }
+
+        impl #discr_name {
+            /// Bytes that introduce this command on the wire, before any parameter.
+            ///
+            /// Empty when the spec has no format for the command.
+            pub const fn prefix_bytes(&self) -> &'static [u8] {
+                match self {
+                    #(#prefix_arms)*
+                }
+            }
+
+            /// The ASCII notation from the command's "[Format]" section, one entry per form.
+            pub const fn ascii_format(&self) -> &'static [&'static str] {
+                match self {
+                    #(#ascii_arms)*
+                }
+            }
+
+            /// Commands whose prefix is the longest one `bytes` starts with.
+            ///
+            /// Several commands share a prefix when they are told apart by a function code or
+            /// a mode (e.g. `FF` in page and standard mode), so this returns all of them.
+            pub fn from_prefix(bytes: &[u8]) -> &'static [Self] {
+                const TABLE: &[(&[u8], &[#discr_name])] = &[
+                    #(#table)*
+                ];
+
+                TABLE
+                    .iter()
+                    .find(|(prefix, _)| bytes.starts_with(prefix))
+                    .map_or(&[], |(_, commands)| commands)
+            }
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use syn::{Ident, Type};
+
+use crate::parameters::ParameterSpec;
+
+/// Wire layout of a command, as declared in the spec's `encoding` field.
+#[derive(Deserialize)]
+pub struct EncodingSpec {
+    /// Bytes that identify the command, e.g. `[27, 74]` for `ESC J`.
+    pub prefix: Vec<u8>,
+    /// Parameters following the prefix. Every non-fixed parameter fills the next content field.
+    #[serde(default)]
+    pub params: Vec<ParamSpec>,
+    /// The parser and writer arms are written by hand; only the prefix is declared.
+    #[serde(default)]
+    pub manual: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ParamSpec {
+    Field(FieldKind),
+    /// Bytes that must appear as-is and aren't stored.
+    Fixed {
+        fixed: Vec<u8>,
+    },
+    /// `pL pH` followed by exactly that many bytes holding the nested parameters.
+    Block {
+        block: Vec<ParamSpec>,
+    },
+    /// Two `u8` fields sharing one byte, the first in the high nibble, each stored as its value
+    /// minus `base`, e.g. the width and height of `GS !`.
+    Nibbles {
+        nibbles: [FieldKind; 2],
+        #[serde(default)]
+        base: u8,
+    },
+    /// A `u8` field from 0 to `digit`, sent either as itself or as its ASCII digit, e.g. `n` of
+    /// `ESC V`. Stored and written as the value.
+    Digit {
+        digit: u8,
+    },
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    U8,
+    /// One byte, only the lowest bit is significant.
+    Bool,
+    LeU16,
+    LeI16,
+    /// The content type's own `parser()` and `to_bytes()`.
+    Type,
+    /// One of the functions of a function-coded command, e.g. `cn fn` and the rest of a
+    /// `GS ( k` block, using the function enum's declared parser and writer.
+    Function,
+}
+
+fn field_count(params: &[ParamSpec]) -> usize {
+    params
+        .iter()
+        .map(|p| match p {
+            ParamSpec::Field(_) => 1,
+            ParamSpec::Fixed { .. } => 0,
+            ParamSpec::Block { block } => field_count(block),
+            ParamSpec::Nibbles { .. } => 2,
+            ParamSpec::Digit { .. } => 1,
+        })
+        .sum()
+}
+
+/// The kind of each content field the parameters fill, in order.
+pub fn field_kinds(params: &[ParamSpec]) -> Vec<FieldKind> {
+    params
+        .iter()
+        .flat_map(|p| match p {
+            ParamSpec::Field(kind) => vec![*kind],
+            ParamSpec::Fixed { .. } => Vec::new(),
+            ParamSpec::Block { block } => field_kinds(block),
+            ParamSpec::Nibbles { nibbles, .. } => nibbles.to_vec(),
+            ParamSpec::Digit { .. } => vec![FieldKind::U8],
+        })
+        .collect()
+}
+
+/// Checks that the declared layout fills exactly the content fields of the command.
+pub fn validate(id: &str, encoding: &EncodingSpec, fields: &[Type]) -> Result<(), String> {
+    if encoding.prefix.is_empty() {
+        return Err(format!("Encoding for id '{id}' has an empty prefix"));
+    }
+
+    if encoding.manual {
+        return Ok(());
+    }
+
+    if !nibbles_are_u8(&encoding.params) {
+        return Err(format!(
+            "Encoding for id '{id}' packs a field other than u8 into nibbles"
+        ));
+    }
+
+    let declared = field_count(&encoding.params);
+    if declared != fields.len() {
+        return Err(format!(
+            "Encoding for id '{id}' declares {declared} field(s) but the command has {} content type(s)",
+            fields.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn nibbles_are_u8(params: &[ParamSpec]) -> bool {
+    params.iter().all(|p| match p {
+        ParamSpec::Nibbles { nibbles, .. } => nibbles.iter().all(|k| matches!(k, FieldKind::U8)),
+        ParamSpec::Block { block } => nibbles_are_u8(block),
+        _ => true,
+    })
+}
+
+fn pattern(enum_name: &Ident, variant: &Ident, fields: &[Ident]) -> TokenStream2 {
+    if fields.is_empty() {
+        quote!( #enum_name::#variant )
+    } else {
+        quote!( #enum_name::#variant( #(#fields),* ) )
+    }
+}
+
+fn parse_params(
+    params: &[ParamSpec],
+    types: &mut std::slice::Iter<'_, Type>,
+    fields: &mut Vec<Ident>,
+    error: &TokenStream2,
+) -> TokenStream2 {
+    let steps = params.iter().map(|param| match param {
+        ParamSpec::Field(kind) => {
+            let ty = types.next().expect("validated field count");
+            let field = format_ident!("f{}", fields.len());
+            fields.push(field.clone());
+
+            match kind {
+                FieldKind::U8 => quote!( let #field = winnow::binary::u8.parse_next(input)?; ),
+                FieldKind::Bool => {
+                    quote!( let #field = winnow::binary::u8.parse_next(input)? & 1 == 1; )
+                }
+                FieldKind::LeU16 => {
+                    quote!( let #field = winnow::binary::le_u16.parse_next(input)?; )
+                }
+                FieldKind::LeI16 => {
+                    quote!( let #field = winnow::binary::le_i16.parse_next(input)?; )
+                }
+                FieldKind::Type => quote!( let #field = <#ty>::parser().parse_next(input)?; ),
+                FieldKind::Function => {
+                    quote!( let #field = <#ty>::parse_declared.parse_next(input)?; )
+                }
+            }
+        }
+        ParamSpec::Fixed { fixed } => {
+            quote!( winnow::token::literal(&[#(#fixed),*][..]).parse_next(input)?; )
+        }
+        ParamSpec::Block { block } => {
+            let first = fields.len();
+            let inner = parse_params(block, types, fields, error);
+            let block_fields = &fields[first..];
+
+            quote! {
+                let ( #(#block_fields,)* ) = {
+                    let len = winnow::binary::le_u16.parse_next(input)?;
+                    let block = winnow::token::take(len).parse_next(input)?;
+
+                    let mut block = winnow::Partial::new(block);
+                    let _ = winnow::stream::StreamIsPartial::complete(&mut block);
+                    let input = &mut block;
+
+                    #inner
+
+                    if !input.is_empty() {
+                        return Err(<#error as winnow::error::ParserError<_>>::from_input(input));
+                    }
+
+                    ( #(#block_fields,)* )
+                };
+            }
+        }
+        ParamSpec::Nibbles { base, .. } => {
+            types.nth(1).expect("validated field count");
+            let high = format_ident!("f{}", fields.len());
+            let low = format_ident!("f{}", fields.len() + 1);
+            fields.extend([high.clone(), low.clone()]);
+
+            quote! {
+                let (#high, #low) = {
+                    let n = winnow::binary::u8.parse_next(input)?;
+                    ((n >> 4) + #base, (n & 0x0F) + #base)
+                };
+            }
+        }
+        ParamSpec::Digit { digit } => {
+            types.next().expect("validated field count");
+            let field = format_ident!("f{}", fields.len());
+            fields.push(field.clone());
+
+            quote! {
+                let #field = winnow::binary::u8
+                    .verify_map(|n: u8| Some(n.checked_sub(b'0').unwrap_or(n)).filter(|n| *n <= #digit))
+                    .parse_next(input)?;
+            }
+        }
+    });
+
+    quote!( #(#steps)* )
+}
+
+fn write_params(
+    params: &[ParamSpec],
+    fields: &mut std::slice::Iter<'_, Ident>,
+    bytes: &Ident,
+) -> TokenStream2 {
+    let steps = params.iter().map(|param| match param {
+        ParamSpec::Field(kind) => {
+            let field = fields.next().expect("validated field count");
+
+            match kind {
+                FieldKind::U8 => quote!( #bytes.push(*#field); ),
+                FieldKind::Bool => quote!( #bytes.push(*#field as u8); ),
+                FieldKind::LeU16 | FieldKind::LeI16 => {
+                    quote!( #bytes.extend_from_slice(&#field.to_le_bytes()); )
+                }
+                FieldKind::Type => quote!( #bytes.extend_from_slice(&#field.to_bytes()); ),
+                FieldKind::Function => {
+                    quote!( #bytes.extend_from_slice(&#field.encode_declared()?); )
+                }
+            }
+        }
+        ParamSpec::Fixed { fixed } => quote!( #bytes.extend_from_slice(&[#(#fixed),*]); ),
+        ParamSpec::Block { block } => {
+            let inner = Ident::new("block", Span::call_site());
+            let steps = write_params(block, fields, &inner);
+
+            quote! {
+                let mut #inner: Vec<u8> = Vec::new();
+                #steps
+                #bytes.extend_from_slice(&(#inner.len() as u16).to_le_bytes());
+                #bytes.extend_from_slice(&#inner);
+            }
+        }
+        ParamSpec::Nibbles { base, .. } => {
+            let high = fields.next().expect("validated field count");
+            let low = fields.next().expect("validated field count");
+            quote! {
+                #bytes.push((#high.wrapping_sub(#base) << 4) | (#low.wrapping_sub(#base) & 0x0F));
+            }
+        }
+        ParamSpec::Digit { .. } => {
+            let field = fields.next().expect("validated field count");
+            quote!( #bytes.push(*#field); )
+        }
+    });
+
+    quote!( #(#steps)* )
+}
+
+/// A command with a declared (non-manual) encoding.
+pub struct Declared<'a> {
+    pub variant: &'a Ident,
+    pub types: &'a [Type],
+    pub encoding: &'a EncodingSpec,
+}
+
+/// Generates `Command::parse_declared` and `Command::encode_declared`.
+pub fn codec_tokens(
+    enum_name: &Ident,
+    declared: &[Declared<'_>],
+    error: Option<&TokenStream2>,
+) -> TokenStream2 {
+    let mut by_prefix: Vec<&Declared<'_>> = declared.iter().collect();
+    // Try longer prefixes first so that `ESC c 3` wins over a bare `ESC c`.
+    by_prefix.sort_by_key(|d| std::cmp::Reverse(d.encoding.prefix.len()));
+
+    let parser = error.map(|error| {
+        let candidates = by_prefix.iter().map(|d| {
+            let prefix = &d.encoding.prefix;
+            let len = prefix.len();
+
+            let mut fields = Vec::new();
+            let steps = parse_params(&d.encoding.params, &mut d.types.iter(), &mut fields, error);
+            let build = pattern(enum_name, d.variant, &fields);
+
+            quote! {
+                if data.starts_with(&[#(#prefix),*]) {
+                    let checkpoint = *input;
+
+                    let result = (|input: &mut winnow::Partial<&'i [u8]>| -> Result<#enum_name, #error> {
+                        winnow::token::take(#len).parse_next(input)?;
+                        #steps
+                        Ok(#build)
+                    })(input);
+
+                    match result {
+                        Err(winnow::error::ErrMode::Backtrack(_)) => *input = checkpoint,
+                        result => return result,
+                    }
+                } else if partial && [#(#prefix),*].starts_with(data) {
+                    return Err(<#error as winnow::error::ParserError<_>>::incomplete(
+                        input,
+                        winnow::error::Needed::Unknown,
+                    ));
+                }
+            }
+        });
+
+        quote! {
+            /// Parse a command whose wire layout is declared in the spec.
+            ///
+            /// Backtracks when no declared command matches the input.
+            pub(crate) fn parse_declared<'i>(
+                input: &mut winnow::Partial<&'i [u8]>,
+            ) -> Result<Self, #error> {
+                use winnow::{Parser as _, stream::StreamIsPartial as _};
+
+                let partial = input.is_partial();
+                let data: &'i [u8] = **input;
+
+                #(#candidates)*
+
+                Err(<#error as winnow::error::ParserError<_>>::from_input(input))
+            }
+        }
+    });
+
+    let writer_arms = declared.iter().map(|d| {
+        let fields: Vec<Ident> = (0..d.types.len()).map(|i| format_ident!("f{i}")).collect();
+        let pat = pattern(enum_name, d.variant, &fields);
+        let prefix = &d.encoding.prefix;
+        let bytes = Ident::new("bytes", Span::call_site());
+        let steps = write_params(&d.encoding.params, &mut fields.iter(), &bytes);
+
+        quote! {
+            #pat => {
+                let mut #bytes: Vec<u8> = vec![#(#prefix),*];
+                #steps
+                Some(#bytes)
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            #parser
+
+            /// Encode a command whose wire layout is declared in the spec, or `None` if its
+            /// encoder is written by hand.
+            #[allow(unused_variables)]
+            pub(crate) fn encode_declared(&self) -> Option<Vec<u8>> {
+                match self {
+                    #(#writer_arms)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Asserts at compile time that the bytes a parameter names for a `type` field are the ones the
+/// content type writes, since `escpos!` encodes them from the spec alone.
+pub fn names_check_tokens(
+    id: &str,
+    encoding: &EncodingSpec,
+    parameters: &[ParameterSpec],
+    types: &[Type],
+) -> TokenStream2 {
+    let kinds = field_kinds(&encoding.params);
+
+    let checks: Vec<TokenStream2> = parameters.iter().flat_map(|p| {
+        let ty = p
+            .field
+            .filter(|f| matches!(kinds.get(*f), Some(FieldKind::Type)))
+            .map(|f| &types[f]);
+
+        ty.into_iter().flat_map(move |ty| {
+            // A variant holding data can't be built from its name alone
+            p.names.iter().filter(|(name, _)| !name.contains('(')).map(move |(name, byte)| {
+                let variant = format_ident!("{name}");
+                let msg = format!(
+                    "{name} doesn't encode to {byte} as the names of parameter '{}' of id '{id}' say",
+                    p.name
+                );
+                quote!( assert!(matches!(<#ty>::#variant.to_bytes(), [#byte]), #msg); )
+            })
+        })
+    }).collect();
+
+    if checks.is_empty() {
+        return quote!();
+    }
+    quote!( const _: () = { #(#checks)* }; )
+}
@@ -0,0 +1,175 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use syn::{Ident, LitStr};
+
+/// One entry of the command page's "[Notes]" section.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum NoteSpec {
+    Text(String),
+    /// A table from the notes, e.g. the bits of a status byte.
+    Table {
+        table: TableSpec,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct TableSpec {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableSpec {
+    /// The table as markdown, one line per row.
+    fn markdown_lines(&self) -> Vec<String> {
+        let row = |cells: &[String]| {
+            let cells: Vec<String> = (0..self.columns.len())
+                .map(|i| cells.get(i).map_or("", String::as_str).replace('|', "\\|"))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![
+            row(&self.columns),
+            format!("|{}", " --- |".repeat(self.columns.len())),
+        ];
+        lines.extend(self.rows.iter().map(|r| row(r)));
+        lines
+    }
+}
+
+impl NoteSpec {
+    /// The note as it reads in `CommandInfo::notes`, with tables in markdown.
+    pub fn text(&self) -> String {
+        match self {
+            NoteSpec::Text(s) => s.clone(),
+            NoteSpec::Table { table } => table.markdown_lines().join("\n"),
+        }
+    }
+}
+
+/// Rustdoc lines for the notes: text as list items, tables as markdown tables.
+pub fn doc_tokens(notes: &[NoteSpec]) -> TokenStream2 {
+    let lines = notes.iter().flat_map(|n| match n {
+        NoteSpec::Text(s) => vec![format!(" - {s}")],
+        // Tables need a blank line on both sides to end the list and render as tables
+        NoteSpec::Table { table } => std::iter::once(String::new())
+            .chain(table.markdown_lines().into_iter().map(|l| format!(" {l}")))
+            .chain(std::iter::once(String::new()))
+            .collect(),
+    });
+
+    let lines = lines.map(|l| {
+        let lit = LitStr::new(&l, Span::call_site());
+        quote!( #[doc = #lit] )
+    });
+
+    quote!( #(#lines)* )
+}
+
+/// What `CommandInfo` holds for one variant.
+pub struct Info<'a> {
+    pub category: &'a Ident,
+    pub id: &'a str,
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub notes: &'a [NoteSpec],
+    pub obsolete: bool,
+}
+
+/// Real-time commands say so in a note of their own.
+pub fn is_realtime(notes: &[NoteSpec]) -> bool {
+    notes.iter().any(|n| match n {
+        NoteSpec::Text(s) => s.starts_with("This is a Real-time command"),
+        NoteSpec::Table { .. } => false,
+    })
+}
+
+/// Commands the printer answers say what they transmit in their description, e.g. "Transmits
+/// the real-time status" or "... and transmits Clear response".
+pub fn transmits(detailed_message: Option<&str>) -> bool {
+    detailed_message.is_some_and(|m| m.to_lowercase().contains("transmit"))
+}
+
+/// Generates `info()` on the command enum and its discriminants, returning `info_ty` filled in
+/// from the spec.
+///
+/// On the enum, the `functions` variants return the info of the function they hold.
+pub fn info_tokens(
+    enum_name: &Ident,
+    category_enum: &Ident,
+    info_ty: &TokenStream2,
+    base_url: Option<&str>,
+    infos: &[Info<'_>],
+    functions: &[Ident],
+) -> TokenStream2 {
+    let discr_name = format_ident!("{}Discriminants", enum_name);
+
+    let entries = infos.iter().map(|i| {
+        let Info {
+            id,
+            name,
+            obsolete,
+            category,
+            ..
+        } = i;
+        let description = i.description.unwrap_or_default();
+        let notes = i.notes.iter().map(NoteSpec::text);
+        let is_realtime = is_realtime(i.notes);
+        let url = match base_url {
+            Some(base) => {
+                let url = LitStr::new(&format!("{base}{id}.html"), Span::call_site());
+                quote!(Some(#url))
+            }
+            None => quote!(None),
+        };
+
+        quote! {
+            #info_ty {
+                id: #id,
+                name: #name,
+                description: #description,
+                category: #category_enum::#category,
+                notes: &[#(#notes),*],
+                obsolete: #obsolete,
+                is_realtime: #is_realtime,
+                url: #url,
+            },
+        }
+    });
+
+    let count = infos.len();
+
+    let command_info = if functions.is_empty() {
+        quote!( #discr_name::from(self).info() )
+    } else {
+        quote! {
+            match self {
+                #(Self::#functions(f) => f.info(),)*
+                command => #discr_name::from(command).info(),
+            }
+        }
+    };
+
+    quote! {
+        impl #discr_name {
+            /// Name, notes and other documentation of the command from the reference.
+            pub const fn info(&self) -> &'static #info_ty {
+                // In declaration order, which is also the order of the discriminants
+                const INFO: &[#info_ty; #count] = &[
+                    #(#entries)*
+                ];
+
+                &INFO[*self as usize]
+            }
+        }
+
+        impl #enum_name {
+            /// Name, notes and other documentation of the command from the reference.
+            pub fn info(&self) -> &'static #info_ty {
+                #command_info
+            }
+        }
+    }
+}
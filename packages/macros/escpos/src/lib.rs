@@ -1,3 +1,11 @@
+mod codes;
+mod encoding;
+mod info;
+mod literal;
+mod models;
+mod parameters;
+mod realtime;
+
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
@@ -6,20 +14,11 @@ use serde::Deserialize;
 use std::{
     collections::{BTreeSet, HashSet},
     env, fs,
+    path::PathBuf,
 };
 use syn::Type;
 use syn::{parse::Parse, parse::ParseStream, parse_macro_input, Expr, Ident, LitStr, Meta, Token};
 
-#[derive(Deserialize)]
-struct Codes {
-    #[serde(default)]
-    ascii: Option<serde_json::Value>,
-    #[serde(default)]
-    hex: Option<serde_json::Value>,
-    #[serde(default)]
-    dec: Option<serde_json::Value>,
-}
-
 #[derive(Deserialize)]
 struct CommandSpec {
     id: String,
@@ -27,24 +26,57 @@ struct CommandSpec {
     category: String,
     #[serde(default)]
     to_string: Option<String>,
+    /// Name of the enum generated for the command's functions, e.g. `SymbolFunction` for
+    /// `GS ( k`.
+    #[serde(default)]
+    functions: Option<String>,
+    /// Id of the command this one is a function of.
+    #[serde(default)]
+    family: Option<String>,
     #[serde(default)]
     message: Option<String>,
     #[serde(default)]
     detailed_message: Option<String>,
     #[serde(default)]
-    notes: Vec<String>,
+    notes: Vec<info::NoteSpec>,
     #[serde(default)]
     obsolete: bool,
     #[serde(default)]
+    codes: Option<codes::Codes>,
+    #[serde(default)]
+    parameters: Vec<parameters::ParameterSpec>,
+    /// `None` when the spec has no model data for the command.
+    #[serde(default)]
+    models: Option<Vec<models::ModelSupportSpec>>,
+    #[serde(default)]
     content: Option<Vec<String>>,
+    #[serde(default)]
+    encoding: Option<encoding::EncodingSpec>,
 }
 
 #[derive(Deserialize)]
 struct RootSpec {
+    /// Where the command pages live, for the links in `CommandInfo`.
+    #[serde(default)]
+    base_url: Option<String>,
     categories: Vec<String>,
+    #[serde(default)]
+    models: Vec<String>,
     commands: Vec<CommandSpec>,
 }
 
+/// The spec `escpos!` reads, and `escpos_commands!` without `SPEC` or `SPEC_PATH`:
+/// `THERMAL_ESCPOS_SPEC` if set, else the one vendored in this repository.
+fn default_spec_path() -> PathBuf {
+    env::var_os("THERMAL_ESCPOS_SPEC").map_or_else(
+        || {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../../../vendor/spec/escpos-commands.json")
+        },
+        PathBuf::from,
+    )
+}
+
 struct MacroArgs {
     spec_expr: Option<Expr>,   // SPEC = include_str!(...)
     spec_path: Option<LitStr>, // SPEC_PATH = "spec/commands.json"
@@ -55,6 +87,18 @@ struct MacroArgs {
     // NEW:
     category_enum_name: Option<Ident>,
     category_derive: Option<LitStr>,
+    model_enum_name: Option<Ident>,
+    model_derive: Option<LitStr>,
+    // Enum of the commands the spec notes as real-time, e.g. `RealtimeCommand`
+    realtime_enum_name: Option<Ident>,
+
+    // Error type of the generated parser, e.g. "ErrMode<ContextError<ErrorCtx>>"
+    parser_error: Option<LitStr>,
+    // Error type returned by the generated `validate()`, e.g. "crate::commands::RangeError".
+    // Requires `command_info`, which it is built with.
+    range_error: Option<LitStr>,
+    // Type returned by the generated `info()`, e.g. "crate::commands::info::CommandInfo"
+    command_info: Option<LitStr>,
 }
 
 impl Parse for MacroArgs {
@@ -68,6 +112,12 @@ impl Parse for MacroArgs {
         // NEW:
         let mut category_enum_name = None;
         let mut category_derive = None;
+        let mut model_enum_name = None;
+        let mut model_derive = None;
+        let mut realtime_enum_name = None;
+        let mut parser_error = None;
+        let mut range_error = None;
+        let mut command_info = None;
 
         while !input.is_empty() {
             let key: Ident = input.parse()?;
@@ -82,10 +132,16 @@ impl Parse for MacroArgs {
                 // NEW:
                 "category_enum_name" => { category_enum_name = Some(input.parse()?); }
                 "category_derive" => { category_derive = Some(input.parse()?); }
+                "model_enum_name" => { model_enum_name = Some(input.parse()?); }
+                "model_derive" => { model_derive = Some(input.parse()?); }
+                "realtime_enum_name" => { realtime_enum_name = Some(input.parse()?); }
+                "parser_error" => { parser_error = Some(input.parse()?); }
+                "range_error" => { range_error = Some(input.parse()?); }
+                "command_info" => { command_info = Some(input.parse()?); }
 
                 _ => return Err(syn::Error::new_spanned(
                     key,
-                    "Unknown key: use SPEC, SPEC_PATH, enum_name, derive, strum_discriminants_derive, category_enum_name, category_derive"
+                    "Unknown key: use SPEC, SPEC_PATH, enum_name, derive, strum_discriminants_derive, category_enum_name, category_derive, model_enum_name, model_derive, realtime_enum_name, parser_error, range_error, command_info"
                 )),
             }
             if input.peek(Token![,]) {
@@ -93,13 +149,6 @@ impl Parse for MacroArgs {
             }
         }
 
-        if spec_expr.is_none() && spec_path.is_none() {
-            return Err(syn::Error::new(
-                Span::call_site(),
-                "Provide SPEC = include_str!(...) or SPEC_PATH = \"path/to/file.json\"",
-            ));
-        }
-
         Ok(MacroArgs {
             spec_expr,
             spec_path,
@@ -109,6 +158,12 @@ impl Parse for MacroArgs {
             discr_derive,
             category_enum_name,
             category_derive,
+            model_enum_name,
+            model_derive,
+            realtime_enum_name,
+            parser_error,
+            range_error,
+            command_info,
         })
     }
 }
@@ -116,7 +171,7 @@ impl Parse for MacroArgs {
 fn variant_tokens_with_ident(
     c: &CommandSpec,
     variant_ident: &syn::Ident,
-    vtypes: &Vec<syn::Type>,
+    vtypes: &[syn::Type],
 ) -> TokenStream2 {
     let message_val = c.message.as_deref().unwrap_or(&c.name);
     let detailed_val = c.detailed_message.as_deref().unwrap_or(message_val);
@@ -129,14 +184,11 @@ fn variant_tokens_with_ident(
     let id_lit = LitStr::new(&c.id, Span::call_site());
     let cat_prop = LitStr::new(&c.category, Span::call_site());
 
-    let mut props_list = Vec::<TokenStream2>::new();
+    let mut props_list = props_kv_tokens(c);
     props_list.push(quote!( id = #id_lit ));
     props_list.push(quote!( category = #cat_prop ));
 
-    let notes_docs = c.notes.iter().map(|n| {
-        let s = LitStr::new(&format!(" - {}", n), Span::call_site());
-        quote!( #[doc = #s] )
-    });
+    let notes_docs = info::doc_tokens(&c.notes);
 
     let obsolete_prop = if c.obsolete {
         Some(quote!(obsolete = "true",))
@@ -144,6 +196,8 @@ fn variant_tokens_with_ident(
         None
     };
 
+    let parameter_docs = parameters::doc_tokens(&c.parameters);
+
     if vtypes.is_empty() {
         // Unit variant
         quote! {
@@ -151,7 +205,8 @@ fn variant_tokens_with_ident(
             #[strum(detailed_message = #detailed_lit)]
             #[strum(to_string = #to_string_lit)]
             #[strum(props( #obsolete_prop #(#props_list),* ))]
-            #(#notes_docs)*
+            #notes_docs
+            #parameter_docs
             #variant_ident,
         }
     } else {
@@ -161,7 +216,8 @@ fn variant_tokens_with_ident(
             #[strum(detailed_message = #detailed_lit)]
             #[strum(to_string = #to_string_lit)]
             #[strum(props( #obsolete_prop #(#props_list),* ))]
-            #(#notes_docs)*
+            #notes_docs
+            #parameter_docs
             #variant_ident( #( #vtypes ),* ),
         }
     }
@@ -177,25 +233,18 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
         discr_derive,
         category_enum_name,
         category_derive,
+        model_enum_name,
+        model_derive,
+        realtime_enum_name,
+        parser_error,
+        range_error,
+        command_info,
     } = parse_macro_input!(input as MacroArgs);
 
-    // Load JSON either from inline string (SPEC) or from file (SPEC_PATH)
-    let spec_string = if let Some(p) = spec_path {
-        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
-        let full = std::path::Path::new(&manifest_dir).join(p.value());
-        match fs::read_to_string(&full) {
-            Ok(s) => s,
-            Err(e) => {
-                return syn::Error::new(
-                    p.span(),
-                    format!("Failed to read {}: {}", full.display(), e),
-                )
-                .to_compile_error()
-                .into();
-            }
-        }
-    } else {
-        let expr = spec_expr.unwrap();
+    // Load JSON from inline string (SPEC), from file (SPEC_PATH), or from the default spec. A
+    // file is read again by the compiler so that a change to it recompiles the caller.
+    let mut spec_include = None;
+    let spec_string = if let Some(expr) = spec_expr {
         match literal_string_from_expr(&expr) {
             Some(s) => s,
             None => {
@@ -207,6 +256,27 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
                 .into();
             }
         }
+    } else {
+        let (full, span) = match spec_path {
+            Some(p) => {
+                let manifest_dir =
+                    env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+                (PathBuf::from(manifest_dir).join(p.value()), p.span())
+            }
+            None => (default_spec_path(), Span::call_site()),
+        };
+        let path = full.display().to_string();
+        spec_include = Some(quote!(
+            const _: &[u8] = include_bytes!(#path);
+        ));
+        match fs::read_to_string(&full) {
+            Ok(s) => s,
+            Err(e) => {
+                return syn::Error::new(span, format!("Failed to read {}: {}", full.display(), e))
+                    .to_compile_error()
+                    .into();
+            }
+        }
     };
 
     let parsed: RootSpec = match serde_json::from_str(&spec_string) {
@@ -280,6 +350,49 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
         }
     };
 
+    // ---------- Generate model enum ----------
+    let mut model_set = BTreeSet::new();
+    for m in &parsed.models {
+        if !model_set.insert(m.clone()) {
+            return syn::Error::new(Span::call_site(), format!("Duplicate model in spec: {m}"))
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    let model_enum_ident =
+        model_enum_name.unwrap_or_else(|| Ident::new("Model", Span::call_site()));
+    let model_variants = parsed.models.iter().map(|m| {
+        let var_ident = make_ident(m);
+
+        let msg_lit = LitStr::new(m, Span::call_site());
+        quote! {
+            #[strum(message = #msg_lit)]
+            #var_ident,
+        }
+    });
+
+    // `supported_on` compares models, so the default derives go beyond the category enum's.
+    let model_meta: Meta = syn::parse_str(&format!(
+        "derive({})",
+        model_derive.as_ref().map(LitStr::value).unwrap_or_else(|| {
+            "Clone, Copy, Debug, PartialEq, Eq, strum::EnumCount, strum::EnumIter, strum::EnumMessage"
+                .to_string()
+        })
+    ))
+    .expect("invalid model_derive");
+
+    // An empty enum can't take the derives, so a spec without model data generates neither the
+    // enum nor the queries on it.
+    let model_enum = (!parsed.models.is_empty()).then(|| {
+        quote! {
+            #[#model_meta]
+            pub enum #model_enum_ident {
+                #(#model_variants)*
+            }
+        }
+    });
+
     // ---------- Generate Command enum ----------
     let derive_attr: Option<TokenStream2> = derive.as_ref().map(|d| {
         let meta: Meta =
@@ -292,31 +405,253 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
         quote!( #[#meta] )
     });
 
-    let mut used_variants: HashSet<String> = HashSet::new();
-    let mut variant_idents: Vec<syn::Ident> = Vec::with_capacity(parsed.commands.len());
-    let mut category_idents: Vec<syn::Ident> = Vec::with_capacity(parsed.commands.len());
-    let mut field_types: Vec<Vec<Type>> = Vec::with_capacity(parsed.commands.len()); // NEW
+    let parse_type = |arg: Option<LitStr>| -> syn::Result<Option<TokenStream2>> {
+        arg.map(|e| e.parse::<Type>().map(|ty| quote!(#ty)))
+            .transpose()
+    };
+    let (parser_error, range_error, command_info) = match (
+        parse_type(parser_error),
+        parse_type(range_error),
+        parse_type(command_info),
+    ) {
+        (Ok(p), Ok(r), Ok(i)) => (p, r, i),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => return e.to_compile_error().into(),
+    };
+
+    // The range error names the command by its info, which works across the function enums
+    if range_error.is_some() && command_info.is_none() {
+        return syn::Error::new(Span::call_site(), "range_error requires command_info")
+            .to_compile_error()
+            .into();
+    }
+
+    let shared = Shared {
+        derive_attr,
+        discr_attr,
+        category_enum: &category_enum_ident,
+        model_enum: (!parsed.models.is_empty()).then_some(&model_enum_ident),
+        models: &parsed.models,
+        parser_error,
+        range_error,
+        command_info,
+        base_url: parsed.base_url.as_deref(),
+    };
+
+    // ---------- Split off the function-coded families ----------
+    let (commands, families) = match split_families(&parsed.commands) {
+        Ok(split) => split,
+        Err(msg) => {
+            return syn::Error::new(Span::call_site(), msg)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let generated = match enum_tokens(&shared, &enum_name, &commands, &families, None) {
+        Ok(generated) => generated,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let cmd_enum = &generated.tokens;
+
+    // ---------- Generate one enum per family ----------
+    let mut function_enums = Vec::with_capacity(families.len());
+    for f in &families {
+        let index = commands
+            .iter()
+            .position(|c| c.id == f.parent.id)
+            .expect("parents stay in the command enum");
+        let parent = Parent {
+            command: &enum_name,
+            variant: &generated.variants[index],
+            framing: f.parent.encoding.as_ref().map(|e| &e.prefix[..]),
+        };
+        match enum_tokens(&shared, &f.enum_name, &f.members, &[], Some(parent)) {
+            Ok(family) => function_enums.push(family.tokens),
+            Err(e) => return e.to_compile_error().into(),
+        }
+    }
+
+    // ---------- Generate the real-time commands ----------
+    let realtime_impl = realtime_enum_name.map(|realtime_enum| {
+        let realtime: Vec<realtime::Realtime<'_>> = commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| info::is_realtime(&c.notes))
+            .map(|(i, c)| realtime::Realtime {
+                spec: c,
+                variant: &generated.variants[i],
+                types: &generated.field_types[i],
+            })
+            .collect();
+        realtime::realtime_tokens(&shared, &enum_name, &realtime_enum, &realtime)
+    });
+
+    // Stitch together
+    quote!( #spec_include #cat_enum #model_enum #cmd_enum #(#function_enums)* #realtime_impl )
+        .into()
+}
+
+/// Commands and text as a `&'static [u8]`, encoded at compile time.
+///
+/// Takes a list of commands by their variant name, e.g. `escpos![InitializePrinter,
+/// SelectJustification(Centered), "Receipt", LineFeed]`. Arguments are integers, `true`/`false`
+/// for flags, or the variant name of a content type the spec gives the bytes of. A function of
+/// a function-coded command can stand on its own, as in `SpecifiesTheOfflineResponse(Enabled)`
+/// for `Response(SpecifiesTheOfflineResponse(Enabled))`. Strings must be ASCII; byte strings
+/// are copied as-is.
+///
+/// Only commands with a declared encoding in the spec can be encoded, and their parameters are
+/// checked against the ranges in the reference. The spec is the one `escpos_commands!` reads
+/// when not given one, so the bytes match the generated `Command`.
+#[proc_macro]
+pub fn escpos(input: TokenStream) -> TokenStream {
+    literal::escpos_tokens(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// What every generated command enum shares: `Command` and the enums of its function-coded
+/// families.
+struct Shared<'a> {
+    derive_attr: Option<TokenStream2>,
+    discr_attr: Option<TokenStream2>,
+    category_enum: &'a Ident,
+    /// `None` when the spec has no model data.
+    model_enum: Option<&'a Ident>,
+    models: &'a [String],
+    parser_error: Option<TokenStream2>,
+    range_error: Option<TokenStream2>,
+    command_info: Option<TokenStream2>,
+    base_url: Option<&'a str>,
+}
+
+/// A command like `GS ( k` whose functions are selected by a code after the shared framing,
+/// generated as a variant holding an enum of the functions.
+struct Family<'a> {
+    parent: &'a CommandSpec,
+    enum_name: Ident,
+    members: Vec<&'a CommandSpec>,
+}
+
+/// Where a function enum is used.
+struct Parent<'a> {
+    command: &'a Ident,
+    variant: &'a Ident,
+    /// The prefix of the parent's encoding, which comes before every function.
+    framing: Option<&'a [u8]>,
+}
+
+/// A generated command enum.
+struct Generated {
+    tokens: TokenStream2,
+    /// The variant of each command, in order.
+    variants: Vec<Ident>,
+    field_types: Vec<Vec<Type>>,
+}
+
+/// Splits the function-coded families off `commands`, returning the commands of the command
+/// enum and the families.
+fn split_families(
+    commands: &[CommandSpec],
+) -> Result<(Vec<&CommandSpec>, Vec<Family<'_>>), String> {
+    let mut families: Vec<Family<'_>> = Vec::new();
+    for c in commands {
+        if let Some(functions) = &c.functions {
+            if c.content.is_some() {
+                return Err(format!(
+                    "Command '{}' declares functions, so its content is implied and can't be set",
+                    c.id
+                ));
+            }
+            let enum_name = syn::parse_str::<Ident>(functions)
+                .map_err(|e| format!("Invalid functions enum name for id '{}': {e}", c.id))?;
+            families.push(Family {
+                parent: c,
+                enum_name,
+                members: Vec::new(),
+            });
+        }
+    }
+
+    let mut top: Vec<&CommandSpec> = Vec::with_capacity(commands.len());
+    for c in commands {
+        let Some(family) = &c.family else {
+            top.push(c);
+            continue;
+        };
+        match families.iter_mut().find(|f| &f.parent.id == family) {
+            Some(f) => f.members.push(c),
+            None => {
+                return Err(format!(
+                    "Command '{}' is a function of '{family}', which doesn't declare functions",
+                    c.id
+                ))
+            }
+        }
+    }
+    if let Some(f) = families.iter().find(|f| f.members.is_empty()) {
+        return Err(format!(
+            "Command '{}' declares functions but none of the commands is one of them",
+            f.parent.id
+        ));
+    }
 
-    for c in &parsed.commands {
-        let base_pretty = c.to_string.as_deref().unwrap_or(&c.name);
-        let vident = uniquify_variant_name(base_pretty, &c.id, &mut used_variants);
-        variant_idents.push(vident);
+    Ok((top, families))
+}
+
+/// The variant of each of `commands`, which make up one enum.
+fn variant_idents(commands: &[&CommandSpec]) -> Vec<Ident> {
+    let mut used = HashSet::new();
+    commands
+        .iter()
+        .map(|c| {
+            let base_pretty = c.to_string.as_deref().unwrap_or(&c.name);
+            uniquify_variant_name(base_pretty, &c.id, &mut used)
+        })
+        .collect()
+}
 
+/// Generates a command enum and everything implemented on it.
+///
+/// `families` are the commands among `commands` whose only field is the enum of their
+/// functions; the generated methods delegate to it.
+fn enum_tokens(
+    shared: &Shared<'_>,
+    enum_name: &Ident,
+    commands: &[&CommandSpec],
+    families: &[Family<'_>],
+    parent: Option<Parent<'_>>,
+) -> syn::Result<Generated> {
+    let Shared {
+        derive_attr,
+        discr_attr,
+        category_enum: category_enum_ident,
+        ..
+    } = shared;
+
+    let variant_idents = variant_idents(commands);
+    let mut category_idents: Vec<syn::Ident> = Vec::with_capacity(commands.len());
+    let mut field_types: Vec<Vec<Type>> = Vec::with_capacity(commands.len());
+    let mut delegated: Vec<syn::Ident> = Vec::new();
+
+    for (c, vident) in commands.iter().zip(&variant_idents) {
         let cat_ident = make_ident(&c.category);
         category_idents.push(cat_ident);
 
         // Parse content strings into syn::Type, with nice errors
         let mut vtypes: Vec<Type> = Vec::new();
-        if let Some(items) = &c.content {
+        if let Some(family) = families.iter().find(|f| f.parent.id == c.id) {
+            let functions = &family.enum_name;
+            vtypes.push(syn::parse_quote!(#functions));
+            delegated.push(vident.clone());
+        } else if let Some(items) = &c.content {
             for (idx, tstr) in items.iter().enumerate() {
                 match syn::parse_str::<Type>(tstr) {
                     Ok(ty) => vtypes.push(ty),
                     Err(e) => {
                         // Point to the bad content with a clear message
                         let msg = format!("Invalid content type for id '{}', index {}: {}\n  hint: use valid Rust types like `u8`, `Option<u16>`, `Vec<u8>`, etc.", c.id, idx, e);
-                        return syn::Error::new(Span::call_site(), msg)
-                            .to_compile_error()
-                            .into();
+                        return Err(syn::Error::new(Span::call_site(), msg));
                     }
                 }
             }
@@ -324,13 +659,21 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
         field_types.push(vtypes);
     }
 
-    let variants = parsed
-        .commands
+    let variants = commands
         .iter()
         .enumerate()
         .map(|(i, c)| variant_tokens_with_ident(c, &variant_idents[i], &field_types[i]));
 
+    let enum_doc = parent.as_ref().map(|p| {
+        let doc = LitStr::new(
+            &format!(" The functions of [`{}::{}`].", p.command, p.variant),
+            Span::call_site(),
+        );
+        quote!( #[doc = #doc] )
+    });
+
     let cmd_enum = quote! {
+        #enum_doc
         #[repr(C)]
         #derive_attr
         #discr_attr
@@ -340,10 +683,12 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
     };
 
     // ---------- Generate Command::category() ----------
-    let category_arms = parsed.commands.iter().enumerate().map(|(i, _c)| {
+    let category_arms = commands.iter().enumerate().map(|(i, _c)| {
         let v = &variant_idents[i];
         let cat = &category_idents[i];
-        if field_types[i].is_empty() {
+        if delegated.contains(v) {
+            quote!( Self::#v(f) => f.category(), )
+        } else if field_types[i].is_empty() {
             quote!( Self::#v => #category_enum_ident::#cat, )
         } else {
             quote!( Self::#v(..) => #category_enum_ident::#cat, )
@@ -361,8 +706,161 @@ pub fn escpos_commands(input: TokenStream) -> TokenStream {
         }
     };
 
-    // Stitch together
-    quote!( #cat_enum #cmd_enum #category_impl ).into()
+    // ---------- Generate declared parser/writer arms ----------
+    let mut declared = Vec::new();
+    let mut names_checks = Vec::new();
+    for (i, c) in commands.iter().enumerate() {
+        let Some(enc) = &c.encoding else {
+            continue;
+        };
+
+        encoding::validate(&c.id, enc, &field_types[i])
+            .map_err(|msg| syn::Error::new(Span::call_site(), msg))?;
+
+        if !enc.manual {
+            names_checks.push(encoding::names_check_tokens(
+                &c.id,
+                enc,
+                &c.parameters,
+                &field_types[i],
+            ));
+            declared.push(encoding::Declared {
+                variant: &variant_idents[i],
+                types: &field_types[i],
+                encoding: enc,
+            });
+        }
+    }
+
+    let codec_impl = encoding::codec_tokens(enum_name, &declared, shared.parser_error.as_ref());
+    let codec_impl = quote!( #codec_impl #(#names_checks)* );
+
+    // ---------- Generate Command::validate() from the parameter ranges ----------
+    let mut checked = Vec::new();
+    for (i, c) in commands.iter().enumerate() {
+        parameters::validate(&c.id, &c.parameters, &field_types[i])
+            .map_err(|msg| syn::Error::new(Span::call_site(), msg))?;
+
+        if c.parameters.iter().any(|p| p.field.is_some()) {
+            checked.push(parameters::Checked {
+                variant: &variant_idents[i],
+                field_count: field_types[i].len(),
+                parameters: &c.parameters,
+            });
+        }
+    }
+
+    let validate_impl = shared
+        .range_error
+        .as_ref()
+        .map(|error| parameters::validate_tokens(enum_name, &checked, &delegated, error));
+
+    // ---------- Generate model support from the model tables ----------
+    let mut supported = Vec::new();
+    for (i, c) in commands.iter().enumerate() {
+        let Some(support) = &c.models else {
+            continue;
+        };
+
+        models::validate(&c.id, support, shared.models)
+            .map_err(|msg| syn::Error::new(Span::call_site(), msg))?;
+
+        supported.push(models::Supported {
+            variant: &variant_idents[i],
+            field_count: field_types[i].len(),
+            parameters: &c.parameters,
+            support,
+        });
+    }
+
+    let models_impl = shared
+        .model_enum
+        .map(|model_enum| models::support_tokens(enum_name, model_enum, &supported, &delegated));
+
+    // ---------- Generate prefix lookups from the [Format] codes ----------
+    // The codes aren't used to parse, so check them against the wire layout where there is one
+    for c in commands {
+        if let Some(codes) = &c.codes {
+            codes
+                .validate(&c.id)
+                .map_err(|msg| syn::Error::new(Span::call_site(), msg))?;
+        }
+
+        let declared = match &parent {
+            Some(p) => p.framing,
+            None => c.encoding.as_ref().map(|e| &e.prefix[..]),
+        };
+        let codes = c.codes.as_ref().and_then(codes::Codes::prefix);
+        if let (Some(declared), Some(codes)) = (declared, codes) {
+            let len = declared.len().min(codes.len());
+            if declared[..len] != codes[..len] {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!(
+                        "Codes of '{}' start with {codes:02X?}, but its encoding with {declared:02X?}",
+                        c.id
+                    ),
+                ));
+            }
+        }
+    }
+
+    let formats: Vec<codes::Format<'_>> = commands
+        .iter()
+        .enumerate()
+        .map(|(i, c)| codes::Format {
+            variant: &variant_idents[i],
+            has_fields: !field_types[i].is_empty(),
+            // A function's own encoding prefix is only its function code
+            prefix: c
+                .codes
+                .as_ref()
+                .and_then(codes::Codes::prefix)
+                .or_else(|| match &parent {
+                    Some(p) => p.framing.map(<[u8]>::to_vec),
+                    None => c.encoding.as_ref().map(|e| e.prefix.clone()),
+                })
+                .unwrap_or_default(),
+            ascii: c
+                .codes
+                .as_ref()
+                .map(codes::Codes::ascii_lines)
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    let codes_impl = codes::format_tokens(enum_name, &formats);
+
+    // ---------- Generate info() from the names and notes ----------
+    let info_impl = shared.command_info.as_ref().map(|info_ty| {
+        let infos: Vec<info::Info<'_>> = commands
+            .iter()
+            .enumerate()
+            .map(|(i, c)| info::Info {
+                category: &category_idents[i],
+                id: &c.id,
+                name: &c.name,
+                description: c.detailed_message.as_deref(),
+                notes: &c.notes,
+                obsolete: c.obsolete,
+            })
+            .collect();
+        info::info_tokens(
+            enum_name,
+            category_enum_ident,
+            info_ty,
+            shared.base_url,
+            &infos,
+            &delegated,
+        )
+    });
+
+    let tokens = quote!( #cmd_enum #category_impl #codec_impl #codes_impl #validate_impl #models_impl #info_impl );
+    Ok(Generated {
+        tokens,
+        variants: variant_idents,
+        field_types,
+    })
 }
 
 // ---------- helpers ----------
@@ -383,10 +881,7 @@ fn variant_tokens(c: &CommandSpec) -> TokenStream2 {
     // also surface category as a strum prop
     props_list.push(quote_kv_prop_ident_val("category", &c.category));
 
-    let notes_docs = c.notes.iter().map(|n| {
-        let s = LitStr::new(&format!(" - {}", n), Span::call_site());
-        quote!( #[doc = #s] )
-    });
+    let notes_docs = info::doc_tokens(&c.notes);
 
     let obsolete_prop = if c.obsolete {
         Some(quote!(obsolete = "true",))
@@ -399,24 +894,24 @@ fn variant_tokens(c: &CommandSpec) -> TokenStream2 {
         #[strum(detailed_message = #detailed_lit)]
         #[strum(to_string = #to_string_lit)]
         #[strum(props( #obsolete_prop #(#props_list),* ))]
-        #(#notes_docs)*
+        #notes_docs
         #variant_ident,
     }
 }
 
 fn props_kv_tokens(c: &CommandSpec) -> Vec<TokenStream2> {
     let mut props = Vec::new();
-    // if let Some(codes) = &c.codes {
-    //     if let Some(v) = &codes.ascii {
-    //         props.push(quote_kv_prop("ascii", v));
-    //     }
-    //     if let Some(v) = &codes.hex {
-    //         props.push(quote_kv_prop("hex", v));
-    //     }
-    //     if let Some(v) = &codes.dec {
-    //         props.push(quote_kv_prop("decimal", v));
-    //     }
-    // }
+    if let Some(codes) = &c.codes {
+        if let Some(v) = &codes.ascii {
+            props.push(quote_kv_prop("ascii", v));
+        }
+        if let Some(v) = &codes.hex {
+            props.push(quote_kv_prop("hex", v));
+        }
+        if let Some(v) = &codes.dec {
+            props.push(quote_kv_prop("decimal", v));
+        }
+    }
     props
 }
 
@@ -0,0 +1,470 @@
+//! `escpos!`: commands and text encoded to bytes at compile time, from the declared encodings.
+
+use once_cell::sync::Lazy;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use std::collections::{BTreeMap, HashMap};
+use syn::{
+    parse::Parser, punctuated::Punctuated, spanned::Spanned, Expr, ExprCall, ExprLit, ExprPath,
+    ExprUnary, Ident, Lit, LitByteStr, Token, UnOp,
+};
+
+use crate::{
+    encoding::{self, FieldKind, ParamSpec},
+    parameters::{self, Bound, ParameterSpec},
+    CommandSpec, RootSpec,
+};
+
+/// The spec `thermal` generates its commands from.
+static PARSED: Lazy<Result<RootSpec, String>> = Lazy::new(|| {
+    let path = crate::default_spec_path();
+    let spec = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&spec).map_err(|e| format!("Failed to parse commands JSON: {e}"))
+});
+
+/// A command as `escpos!` names it: its variant name.
+struct Named<'a> {
+    spec: &'a CommandSpec,
+    variant: String,
+}
+
+/// The commands by variant name, and the functions of each function-coded command.
+struct Lookup<'a> {
+    commands: HashMap<String, Named<'a>>,
+    /// By the parent's id.
+    functions: HashMap<&'a str, Vec<Named<'a>>>,
+}
+
+impl<'a> Lookup<'a> {
+    fn new(spec: &'a RootSpec) -> Result<Self, String> {
+        let (commands, families) = crate::split_families(&spec.commands)?;
+
+        let named = |commands: &[&'a CommandSpec]| -> Vec<Named<'a>> {
+            commands
+                .iter()
+                .zip(crate::variant_idents(commands))
+                .map(|(spec, variant)| Named {
+                    spec,
+                    variant: variant.to_string(),
+                })
+                .collect()
+        };
+
+        Ok(Lookup {
+            commands: named(&commands)
+                .into_iter()
+                .map(|n| (n.variant.clone(), n))
+                .collect(),
+            functions: families
+                .iter()
+                .map(|f| (f.parent.id.as_str(), named(&f.members)))
+                .collect(),
+        })
+    }
+
+    fn function(&self, parent: &CommandSpec, name: &str) -> Option<&Named<'a>> {
+        self.functions
+            .get(parent.id.as_str())?
+            .iter()
+            .find(|n| n.variant == name)
+    }
+
+    /// The commands with a function called `name`, for writing a function without its command.
+    fn parents_of(&self, name: &str) -> Vec<&Named<'a>> {
+        self.commands
+            .values()
+            .filter(|c| self.function(c.spec, name).is_some())
+            .collect()
+    }
+}
+
+/// `Name` or `Name(args)`.
+fn call(expr: &Expr) -> Option<(&Ident, Vec<&Expr>)> {
+    match expr {
+        Expr::Path(ExprPath { path, .. }) => Some((path.get_ident()?, Vec::new())),
+        Expr::Call(ExprCall { func, args, .. }) => match &**func {
+            Expr::Path(ExprPath { path, .. }) => Some((path.get_ident()?, args.iter().collect())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// An integer literal, possibly negated, or a byte literal like `b'0'`.
+fn integer(expr: &Expr) -> Option<syn::Result<i64>> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => Some(i.base10_parse()),
+        Expr::Lit(ExprLit {
+            lit: Lit::Byte(b), ..
+        }) => Some(Ok(b.value().into())),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => integer(expr).map(|v| v.map(|v| -v)),
+        _ => None,
+    }
+}
+
+fn error<T>(spanned: &impl Spanned, msg: impl std::fmt::Display) -> syn::Result<T> {
+    Err(syn::Error::new(spanned.span(), msg))
+}
+
+/// A content field, encoded.
+struct Field {
+    bytes: Vec<u8>,
+    /// For checking the parameter held in the field.
+    value: Option<i64>,
+}
+
+struct Encoder<'a> {
+    lookup: Lookup<'a>,
+}
+
+impl Encoder<'_> {
+    /// One item of the list: text, raw bytes or a command.
+    fn item(&self, expr: &Expr) -> syn::Result<Vec<u8>> {
+        match expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => {
+                let text = s.value();
+                // Anything else depends on the code page the printer has selected
+                if !text.is_ascii() {
+                    return error(s, "escpos! only takes ASCII text; write text in other code pages as a byte string");
+                }
+                // These would be read as commands, not printed
+                if let Some(b) = text
+                    .bytes()
+                    .find(|b| *b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t'))
+                {
+                    return error(
+                        s,
+                        format!("text can't hold the control byte 0x{b:02X}; write the command by name or as a byte string"),
+                    );
+                }
+                Ok(text.into_bytes())
+            }
+            Expr::Lit(ExprLit {
+                lit: Lit::ByteStr(b),
+                ..
+            }) => Ok(b.value()),
+            _ => self.command(expr),
+        }
+    }
+
+    fn command(&self, expr: &Expr) -> syn::Result<Vec<u8>> {
+        let Some((name, args)) = call(expr) else {
+            return error(
+                expr,
+                "expected a command like `LineFeed` or `SelectJustification(Centered)`, a string or a byte string",
+            );
+        };
+        let variant = name.to_string();
+
+        if let Some(command) = self.lookup.commands.get(&variant) {
+            return self.encode(command, name, &args);
+        }
+
+        // A function on its own stands for its command holding it
+        match &self.lookup.parents_of(&variant)[..] {
+            [] => error(name, format!("unknown command `{variant}`")),
+            [parent] => self.encode(parent, name, &[expr]),
+            parents => {
+                let names: Vec<String> =
+                    parents.iter().map(|p| format!("`{}`", p.variant)).collect();
+                error(
+                    name,
+                    format!(
+                        "`{variant}` is a function of {}; write it inside its command",
+                        names.join(" and ")
+                    ),
+                )
+            }
+        }
+    }
+
+    fn encode(&self, command: &Named<'_>, name: &Ident, args: &[&Expr]) -> syn::Result<Vec<u8>> {
+        let Named { spec, variant } = command;
+        let Some(enc) = spec.encoding.as_ref().filter(|e| !e.manual) else {
+            return error(
+                name,
+                format!("`{variant}` has no declared encoding in the spec; write it at runtime with `Command::write_to`"),
+            );
+        };
+
+        let kinds = encoding::field_kinds(&enc.params);
+        if args.len() != kinds.len() {
+            return error(
+                name,
+                format!("`{variant}` takes {} argument(s)", kinds.len()),
+            );
+        }
+
+        let fields = kinds
+            .iter()
+            .zip(args)
+            .enumerate()
+            .map(|(i, (kind, arg))| {
+                let param = spec.parameters.iter().find(|p| p.field == Some(i));
+                self.field(spec, *kind, param, arg)
+            })
+            .collect::<syn::Result<Vec<Field>>>()?;
+
+        check(spec, variant, &fields, args)?;
+
+        let mut bytes = enc.prefix.clone();
+        write(&enc.params, &mut fields.into_iter(), &mut bytes);
+        Ok(bytes)
+    }
+
+    fn field(
+        &self,
+        spec: &CommandSpec,
+        kind: FieldKind,
+        param: Option<&ParameterSpec>,
+        arg: &Expr,
+    ) -> syn::Result<Field> {
+        let names = param.map(|p| &p.names).filter(|n| !n.is_empty());
+        let named = |arg: &Expr| -> Option<syn::Result<i64>> {
+            let ident = match arg {
+                Expr::Path(ExprPath { path, .. }) => path.get_ident()?,
+                _ => return None,
+            };
+            let byte = names?.get(&ident.to_string()).map(|b| i64::from(*b));
+            Some(byte.ok_or_else(|| {
+                let mut known: Vec<(&String, &u8)> = names.into_iter().flatten().collect();
+                known.sort_by_key(|(_, b)| **b);
+                let known: Vec<String> = known.iter().map(|(n, _)| format!("`{n}`")).collect();
+                syn::Error::new(
+                    ident.span(),
+                    format!("expected one of {}", known.join(", ")),
+                )
+            }))
+        };
+
+        let number = |min: i64, max: i64| -> syn::Result<i64> {
+            let value = match (integer(arg), named(arg)) {
+                (Some(v), _) | (None, Some(v)) => v?,
+                (None, None) => return error(arg, "expected an integer"),
+            };
+            if !(min..=max).contains(&value) {
+                return error(arg, format!("expected a value from {min} to {max}"));
+            }
+            Ok(value)
+        };
+
+        Ok(match kind {
+            FieldKind::U8 => {
+                let v = number(0, 255)?;
+                Field {
+                    bytes: vec![v as u8],
+                    value: Some(v),
+                }
+            }
+            FieldKind::Bool => {
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Bool(b), ..
+                }) = arg
+                else {
+                    return error(arg, "expected `true` or `false`");
+                };
+                Field {
+                    bytes: vec![b.value as u8],
+                    value: Some(b.value as i64),
+                }
+            }
+            FieldKind::LeU16 => {
+                let v = number(0, u16::MAX.into())?;
+                Field {
+                    bytes: (v as u16).to_le_bytes().to_vec(),
+                    value: Some(v),
+                }
+            }
+            FieldKind::LeI16 => {
+                let v = number(i16::MIN.into(), i16::MAX.into())?;
+                Field {
+                    bytes: (v as i16).to_le_bytes().to_vec(),
+                    value: Some(v),
+                }
+            }
+            FieldKind::Type => {
+                // A content type that reads any byte in its range, like a set of flags
+                let ranged = param.is_some_and(|p| p.min.is_some() || p.max.is_some());
+                if names.is_none() && !ranged {
+                    return error(
+                        arg,
+                        "the spec doesn't name the values of this field's type, so escpos! can't encode it",
+                    );
+                }
+
+                if let Some(names) = names {
+                    if let Some(field) = data_variant(names, arg) {
+                        return field;
+                    }
+                }
+
+                let v = number(0, 255)?;
+                // Other bytes may not parse back into the content type
+                let known = ranged
+                    || names.is_some_and(|n| n.values().any(|b| i64::from(*b) == v))
+                    || param.is_some_and(|p| p.values.contains(&v));
+                if !known {
+                    return error(arg, format!("{v} isn't a value of this field"));
+                }
+                Field {
+                    bytes: vec![v as u8],
+                    value: Some(v),
+                }
+            }
+            FieldKind::Function => {
+                let function = call(arg).and_then(|(name, args)| {
+                    let function = self.lookup.function(spec, &name.to_string())?;
+                    Some((function, name, args))
+                });
+                let Some((function, name, args)) = function else {
+                    return error(arg, "expected one of the command's functions");
+                };
+                Field {
+                    bytes: self.encode(function, name, &args)?,
+                    value: None,
+                }
+            }
+        })
+    }
+}
+
+/// A variant holding data, like `FeedAndCut(3, Full)`: the byte named for its unit fields, then
+/// its integers. `None` if `arg` isn't a call.
+fn data_variant(names: &BTreeMap<String, u8>, arg: &Expr) -> Option<syn::Result<Field>> {
+    let (name, args) = call(arg).filter(|(_, args)| !args.is_empty())?;
+
+    let mut fields = Vec::new();
+    let mut integers = Vec::new();
+    for arg in &args {
+        match integer(arg) {
+            Some(v) => {
+                fields.push("_".to_string());
+                integers.push((v, arg));
+            }
+            None => match call(arg) {
+                Some((unit, unit_args)) if unit_args.is_empty() => fields.push(unit.to_string()),
+                _ => return Some(error(arg, "expected an integer or a variant name")),
+            },
+        }
+    }
+
+    let key = format!("{name}({})", fields.join(", "));
+    let Some(byte) = names.get(&key) else {
+        return Some(error(arg, format!("`{key}` isn't a value of this field")));
+    };
+
+    let mut bytes = vec![*byte];
+    for (v, arg) in integers {
+        match v {
+            Ok(v @ 0..=255) => bytes.push(v as u8),
+            Ok(_) => return Some(error(arg, "expected a value from 0 to 255")),
+            Err(e) => return Some(Err(e)),
+        }
+    }
+
+    Some(Ok(Field {
+        bytes,
+        value: Some(i64::from(*byte)),
+    }))
+}
+
+/// Checks the parameters mapped to a field against their ranges, as `Command::validate` would.
+fn check(spec: &CommandSpec, variant: &str, fields: &[Field], args: &[&Expr]) -> syn::Result<()> {
+    let value = |p: &ParameterSpec| -> Option<i64> {
+        // An accessor reads something other than the field's byte
+        if p.accessor.is_some() {
+            return None;
+        }
+        fields[p.field?].value
+    };
+    let bound = |b: &Bound| match b {
+        Bound::Value(v) => Some(*v),
+        Bound::Param(other) => spec
+            .parameters
+            .iter()
+            .find(|p| &p.name == other)
+            .and_then(value),
+    };
+
+    for p in &spec.parameters {
+        let Some(v) = value(p) else {
+            continue;
+        };
+
+        let in_range = (p.values.is_empty() || p.values.contains(&v))
+            && p.min.as_ref().and_then(bound).is_none_or(|min| v >= min)
+            && p.max.as_ref().and_then(bound).is_none_or(|max| v <= max);
+        if !in_range {
+            let arg = args[p.field.expect("only mapped parameters have a value")];
+            return error(
+                arg,
+                format!(
+                    "`{}` of `{variant}` is out of range: {}",
+                    p.name,
+                    parameters::describe(p)
+                ),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn write(params: &[ParamSpec], fields: &mut impl Iterator<Item = Field>, bytes: &mut Vec<u8>) {
+    for param in params {
+        match param {
+            ParamSpec::Field(_) | ParamSpec::Digit { .. } => {
+                bytes.extend(fields.next().expect("one field per argument").bytes);
+            }
+            ParamSpec::Fixed { fixed } => bytes.extend(fixed),
+            ParamSpec::Block { block } => {
+                let mut inner = Vec::new();
+                write(block, fields, &mut inner);
+                bytes.extend((inner.len() as u16).to_le_bytes());
+                bytes.extend(inner);
+            }
+            ParamSpec::Nibbles { base, .. } => {
+                let mut nibble = || {
+                    let value = fields.next().expect("one field per argument").value;
+                    (value.expect("nibbles hold integers") - i64::from(*base)) as u8 & 0x0F
+                };
+                let high = nibble();
+                bytes.push((high << 4) | nibble());
+            }
+        }
+    }
+}
+
+/// Expands `escpos![...]` into a `&'static [u8]`.
+pub fn escpos_tokens(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let items = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(input)?;
+
+    let spec = PARSED
+        .as_ref()
+        .map_err(|msg| syn::Error::new(Span::call_site(), msg))?;
+    let encoder = Encoder {
+        lookup: Lookup::new(spec).map_err(|msg| syn::Error::new(Span::call_site(), msg))?,
+    };
+
+    let mut bytes = Vec::new();
+    for item in &items {
+        bytes.extend(encoder.item(item)?);
+    }
+
+    // Read again by the compiler so that a change to the spec recompiles the caller
+    let spec_path = crate::default_spec_path().display().to_string();
+    let bytes = LitByteStr::new(&bytes, Span::call_site());
+    Ok(quote!({
+        const _: &[u8] = include_bytes!(#spec_path);
+        #bytes as &'static [u8]
+    }))
+}
@@ -0,0 +1,148 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use syn::Ident;
+
+use crate::parameters::{self, ParameterSpec};
+
+/// A model the command page lists the command for.
+#[derive(Deserialize)]
+pub struct ModelSupportSpec {
+    pub model: String,
+    /// Narrower ranges the model accepts, matched to the command's parameters by name.
+    #[serde(default)]
+    pub parameters: Vec<ParameterSpec>,
+}
+
+/// Checks that every model is declared at the top level.
+///
+/// Restrictions on parameters that aren't mapped to a content field are kept as data but not
+/// checked, like the command's own ranges.
+pub fn validate(id: &str, support: &[ModelSupportSpec], models: &[String]) -> Result<(), String> {
+    for s in support {
+        if !models.contains(&s.model) {
+            return Err(format!("Command '{id}' lists unknown model '{}'", s.model));
+        }
+    }
+
+    Ok(())
+}
+
+/// A command with model data.
+pub struct Supported<'a> {
+    pub variant: &'a Ident,
+    pub field_count: usize,
+    pub parameters: &'a [ParameterSpec],
+    pub support: &'a [ModelSupportSpec],
+}
+
+/// Generates `CommandDiscriminants::models` and `Command::supported_on`.
+///
+/// The `functions` variants hold a function enum, whose model data is more specific than the
+/// overview page's.
+pub fn support_tokens(
+    enum_name: &Ident,
+    model_enum: &Ident,
+    supported: &[Supported<'_>],
+    functions: &[Ident],
+) -> TokenStream2 {
+    let discr_name = format_ident!("{}Discriminants", enum_name);
+
+    let models_arms = supported.iter().map(|s| {
+        let v = s.variant;
+        let listed = s.support.iter().map(|m| crate::make_ident(&m.model));
+        quote!( Self::#v => Some(&[#(#model_enum::#listed),*]), )
+    });
+
+    // Only commands whose models narrow a mapped parameter need to look at their content.
+    let restricted = supported.iter().filter_map(|s| {
+        let mapped = |name: &str| {
+            s.parameters
+                .iter()
+                .find(|p| p.name == name && p.field.is_some())
+        };
+
+        let model_arms: Vec<TokenStream2> = s
+            .support
+            .iter()
+            .filter(|m| m.parameters.iter().any(|r| mapped(&r.name).is_some()))
+            .map(|m| {
+                let model = crate::make_ident(&m.model);
+                let checks = m
+                    .parameters
+                    .iter()
+                    .filter(|r| mapped(&r.name).is_some())
+                    .map(|r| parameters::check_tokens(r, |_| quote!(return Some(false);)));
+                quote! {
+                    #model_enum::#model => {
+                        #(#checks)*
+                        Some(true)
+                    }
+                }
+            })
+            .collect();
+
+        if model_arms.is_empty() {
+            return None;
+        }
+
+        let v = s.variant;
+        let fields: Vec<Ident> = (0..s.field_count).map(|i| format_ident!("f{i}")).collect();
+        let reads = s
+            .parameters
+            .iter()
+            .filter(|p| p.field.is_some())
+            .map(|p| parameters::read_tokens(p, &fields));
+
+        Some(quote! {
+            Self::#v( #(#fields),* ) => {
+                #(#reads)*
+                match model {
+                    #(#model_arms)*
+                    model => #discr_name::#v.models().map(|m| m.contains(&model)),
+                }
+            }
+        })
+    });
+
+    quote! {
+        impl #discr_name {
+            /// Models the reference lists this command for, or `None` if the spec has no model
+            /// data for it.
+            pub const fn models(&self) -> Option<&'static [#model_enum]> {
+                match self {
+                    #(#models_arms)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl #enum_name {
+            /// Whether `model` accepts this command with these parameters, or `None` if the spec
+            /// has no model data for it.
+            ///
+            /// Few pages have their model tables scraped yet, and some of the data is overrides
+            /// in content.json, so `None` only means the spec doesn't say. Callers decide what
+            /// an unknown answer means for them.
+            #[allow(unused_variables)]
+            pub fn supported_on(&self, model: #model_enum) -> Option<bool> {
+                match self {
+                    #(#restricted)*
+                    #(
+                        Self::#functions(f) => {
+                            let listed = #discr_name::#functions.models().map(|m| m.contains(&model));
+                            match (listed, f.supported_on(model)) {
+                                (Some(false), _) | (_, Some(false)) => Some(false),
+                                (None, None) => None,
+                                _ => Some(true),
+                            }
+                        }
+                    )*
+                    command => #discr_name::from(command)
+                        .models()
+                        .map(|m| m.contains(&model)),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use syn::{Expr, Ident, LitStr, Type};
+
+/// A parameter from the command page's "[Range]" and "[Default]" sections.
+#[derive(Deserialize)]
+pub struct ParameterSpec {
+    pub name: String,
+    /// The range as written in the reference, e.g. `1 ≤ n ≤ 8`.
+    #[serde(default)]
+    pub range: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub min: Option<Bound>,
+    #[serde(default)]
+    pub max: Option<Bound>,
+    /// Allowed values, when the range is a list rather than an interval.
+    #[serde(default)]
+    pub values: Vec<i64>,
+    /// Index of the content field holding the parameter. Parameters without one aren't checked.
+    #[serde(default)]
+    pub field: Option<usize>,
+    /// Method reading the parameter out of the field, for fields of a content type.
+    #[serde(default)]
+    pub accessor: Option<String>,
+    /// The byte each variant of the field's content type encodes to, by variant name. A variant
+    /// holding data is named with its unit fields and `_` for its integers, e.g.
+    /// `FeedAndCut(_, Full)`, and its integers follow the byte.
+    #[serde(default)]
+    pub names: BTreeMap<String, u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Bound {
+    Value(i64),
+    /// Another parameter of the same command, as in `t1 ≤ t2`.
+    Param(String),
+}
+
+/// Checks that every checked parameter points at an existing field, and that parameter bounds
+/// refer to other checked parameters.
+pub fn validate(id: &str, parameters: &[ParameterSpec], fields: &[Type]) -> Result<(), String> {
+    for p in parameters {
+        let Some(field) = p.field else {
+            if !p.names.is_empty() {
+                return Err(format!(
+                    "Parameter '{}' of id '{id}' names its values but isn't mapped to a field",
+                    p.name
+                ));
+            }
+            continue;
+        };
+
+        if let Some(name) = p.names.keys().find(|n| !is_variant_name(n)) {
+            return Err(format!(
+                "Parameter '{}' of id '{id}' names a value '{name}', which isn't a variant name",
+                p.name
+            ));
+        }
+
+        if field >= fields.len() {
+            return Err(format!(
+                "Parameter '{}' of id '{id}' refers to field {field} but the command has {} content type(s)",
+                p.name,
+                fields.len()
+            ));
+        }
+
+        for bound in [&p.min, &p.max].into_iter().flatten() {
+            if let Bound::Param(other) = bound {
+                if !parameters
+                    .iter()
+                    .any(|o| &o.name == other && o.field.is_some())
+                {
+                    return Err(format!(
+                        "Parameter '{}' of id '{id}' is bounded by '{other}', which isn't a checked parameter",
+                        p.name
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `Variant`, or `Variant(..)` with unit variant names and `_` as its fields.
+fn is_variant_name(name: &str) -> bool {
+    match syn::parse_str::<Expr>(name) {
+        Ok(Expr::Path(path)) => path.path.get_ident().is_some(),
+        Ok(Expr::Call(call)) => {
+            matches!(&*call.func, Expr::Path(f) if f.path.get_ident().is_some())
+                && call.args.iter().all(|arg| match arg {
+                    Expr::Path(a) => a.path.get_ident().is_some(),
+                    Expr::Infer(_) => true,
+                    _ => false,
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Rustdoc lines listing the parameters, appended to the variant's notes.
+pub fn doc_tokens(parameters: &[ParameterSpec]) -> TokenStream2 {
+    if parameters.is_empty() {
+        return quote!();
+    }
+
+    let lines = parameters.iter().map(|p| {
+        let mut line = format!(" - `{}`", p.name);
+        if let Some(range) = &p.range {
+            line.push_str(&format!(": {range}"));
+        }
+        if let Some(default) = &p.default {
+            line.push_str(&format!(" (default {default})"));
+        }
+        let lit = LitStr::new(&line, Span::call_site());
+        quote!( #[doc = #lit] )
+    });
+
+    quote! {
+        #[doc = ""]
+        #[doc = " Parameters:"]
+        #(#lines)*
+    }
+}
+
+/// The range of `p` as the reference writes it, or as close as the bounds allow.
+pub fn describe(p: &ParameterSpec) -> String {
+    if let Some(range) = &p.range {
+        return range.clone();
+    }
+
+    let bound = |b: &Bound| match b {
+        Bound::Value(v) => v.to_string(),
+        Bound::Param(name) => name.clone(),
+    };
+
+    match (&p.min, &p.max) {
+        _ if !p.values.is_empty() => format!(
+            "{} = {}",
+            p.name,
+            p.values
+                .iter()
+                .map(i64::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (Some(min), Some(max)) => format!("{} ≤ {} ≤ {}", bound(min), p.name, bound(max)),
+        (Some(min), None) => format!("{} ≤ {}", bound(min), p.name),
+        (None, Some(max)) => format!("{} ≤ {}", p.name, bound(max)),
+        (None, None) => String::new(),
+    }
+}
+
+/// Local variable holding the value of parameter `name` in generated code.
+pub fn var(name: &str) -> Ident {
+    format_ident!(
+        "p_{}",
+        name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+    )
+}
+
+/// Reads a parameter mapped to a content field into its [`var`].
+pub fn read_tokens(p: &ParameterSpec, fields: &[Ident]) -> TokenStream2 {
+    let name = var(&p.name);
+    let field = &fields[p.field.expect("only mapped parameters are read")];
+    match &p.accessor {
+        Some(accessor) => {
+            let accessor = format_ident!("{}", accessor);
+            quote!( let #name = #field.#accessor() as i64; )
+        }
+        None => quote!( let #name = *#field as i64; ),
+    }
+}
+
+/// Checks the bounds of `p` against its [`var`], running `fail(range)` for the first one that
+/// doesn't hold.
+pub fn check_tokens(p: &ParameterSpec, fail: impl Fn(String) -> TokenStream2) -> TokenStream2 {
+    let name = var(&p.name);
+    let mut checks = Vec::new();
+    let bound = |b: &Bound| match b {
+        Bound::Value(v) => quote!( #v ),
+        Bound::Param(other) => {
+            let other = var(other);
+            quote!( #other )
+        }
+    };
+
+    if !p.values.is_empty() {
+        let values = &p.values;
+        let fail = fail(describe(p));
+        checks.push(quote!( if !matches!(#name, #(#values)|*) { #fail } ));
+    }
+
+    // Numeric bounds are reported with the reference's range, parameter bounds with the
+    // relation itself since the reference lists those on a line of their own.
+    for (b, is_min) in [(&p.min, true), (&p.max, false)] {
+        let Some(b) = b else {
+            continue;
+        };
+        let limit = bound(b);
+        let range = match b {
+            Bound::Param(other) if is_min => format!("{other} ≤ {}", p.name),
+            Bound::Param(other) => format!("{} ≤ {other}", p.name),
+            Bound::Value(_) => describe(p),
+        };
+        let fail = fail(range);
+        checks.push(if is_min {
+            quote!( if #name < #limit { #fail } )
+        } else {
+            quote!( if #name > #limit { #fail } )
+        });
+    }
+
+    quote!( #(#checks)* )
+}
+
+/// A command whose content has checked parameters.
+pub struct Checked<'a> {
+    pub variant: &'a Ident,
+    pub field_count: usize,
+    pub parameters: &'a [ParameterSpec],
+}
+
+/// Generates `Command::validate`, returning `error` for the first parameter out of range.
+///
+/// The `functions` variants hold a function enum and check it instead.
+pub fn validate_tokens(
+    enum_name: &Ident,
+    checked: &[Checked<'_>],
+    functions: &[Ident],
+    error: &TokenStream2,
+) -> TokenStream2 {
+    let discr_name = format_ident!("{}Discriminants", enum_name);
+
+    let arms = checked.iter().map(|c| {
+        let v = c.variant;
+        let fields: Vec<Ident> = (0..c.field_count).map(|i| format_ident!("f{i}")).collect();
+        let params: Vec<&ParameterSpec> =
+            c.parameters.iter().filter(|p| p.field.is_some()).collect();
+
+        let reads = params.iter().map(|p| read_tokens(p, &fields));
+        let checks = params.iter().map(|p| {
+            let parameter = LitStr::new(&p.name, Span::call_site());
+            check_tokens(p, |range| {
+                let range = LitStr::new(&range, Span::call_site());
+                quote! {
+                    return Err(#error {
+                        command: #discr_name::#v.info(),
+                        parameter: #parameter,
+                        range: #range,
+                    });
+                }
+            })
+        });
+
+        quote! {
+            Self::#v( #(#fields),* ) => {
+                #(#reads)*
+                #(#checks)*
+            }
+        }
+    });
+
+    quote! {
+        impl #enum_name {
+            /// Check the command's parameters against the ranges in the reference.
+            ///
+            /// Only parameters the spec maps to a content field are checked.
+            #[allow(unused_variables)]
+            pub fn validate(&self) -> Result<(), #error> {
+                match self {
+                    #(#arms)*
+                    #(Self::#functions(f) => f.validate()?,)*
+                    _ => {}
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{Ident, Type};
+
+use crate::{CommandSpec, Shared};
+
+/// A command whose page notes it as a real-time command.
+pub struct Realtime<'a> {
+    pub spec: &'a CommandSpec,
+    pub variant: &'a Ident,
+    pub types: &'a [Type],
+}
+
+fn pattern(enum_name: &Ident, variant: &Ident, fields: &[Ident]) -> TokenStream2 {
+    if fields.is_empty() {
+        quote!( #enum_name::#variant )
+    } else {
+        quote!( #enum_name::#variant( #(#fields),* ) )
+    }
+}
+
+/// Generates the enum of the real-time commands, the conversions between it and the command
+/// enum, and `Command::as_realtime`/`is_realtime`.
+pub fn realtime_tokens(
+    shared: &Shared<'_>,
+    enum_name: &Ident,
+    realtime_enum: &Ident,
+    realtime: &[Realtime<'_>],
+) -> TokenStream2 {
+    // An empty enum can't take the derives
+    if realtime.is_empty() {
+        return syn::Error::new(Span::call_site(), "No command is noted as real-time")
+            .to_compile_error();
+    }
+
+    let Shared {
+        derive_attr,
+        discr_attr,
+        ..
+    } = shared;
+    let discr_name = format_ident!("{}Discriminants", enum_name);
+    let realtime_discr = format_ident!("{}Discriminants", realtime_enum);
+
+    let variants = realtime
+        .iter()
+        .map(|r| crate::variant_tokens_with_ident(r.spec, r.variant, r.types));
+
+    let patterns: Vec<(TokenStream2, TokenStream2)> = realtime
+        .iter()
+        .map(|r| {
+            let fields: Vec<Ident> = (0..r.types.len()).map(|i| format_ident!("f{i}")).collect();
+            (
+                pattern(enum_name, r.variant, &fields),
+                pattern(realtime_enum, r.variant, &fields),
+            )
+        })
+        .collect();
+
+    let into_arms = patterns.iter().map(|(cmd, rt)| quote!( #rt => #cmd, ));
+    let from_arms = patterns.iter().map(|(cmd, rt)| quote!( #cmd => Ok(#rt), ));
+
+    let discr_arms = realtime.iter().map(|r| {
+        let v = r.variant;
+        if r.types.is_empty() {
+            quote!( Self::#v => Some(#realtime_discr::#v), )
+        } else {
+            quote!( Self::#v(..) => Some(#realtime_discr::#v), )
+        }
+    });
+
+    let replies: Vec<&Ident> = realtime
+        .iter()
+        .filter(|r| crate::info::transmits(r.spec.detailed_message.as_deref()))
+        .map(|r| r.variant)
+        .collect();
+    let expects_reply = if replies.is_empty() {
+        quote!(false)
+    } else {
+        quote!(matches!(self, #(Self::#replies)|*))
+    };
+
+    let same_arms = realtime.iter().map(|r| {
+        let v = r.variant;
+        quote!( #realtime_discr::#v => #discr_name::#v, )
+    });
+
+    quote! {
+        /// Commands the printer processes as soon as they arrive, ahead of the data waiting in
+        /// its receive buffer.
+        #[repr(C)]
+        #derive_attr
+        #discr_attr
+        pub enum #realtime_enum {
+            #(#variants)*
+        }
+
+        impl From<#realtime_enum> for #enum_name {
+            fn from(command: #realtime_enum) -> Self {
+                match command {
+                    #(#into_arms)*
+                }
+            }
+        }
+
+        impl TryFrom<#enum_name> for #realtime_enum {
+            type Error = #enum_name;
+
+            /// Fails with the command itself if it isn't a real-time command.
+            fn try_from(command: #enum_name) -> Result<Self, Self::Error> {
+                match command {
+                    #(#from_arms)*
+                    command => Err(command),
+                }
+            }
+        }
+
+        impl #realtime_discr {
+            /// Whether the printer answers the command. It must not be sent again until the
+            /// answer has arrived.
+            pub const fn expects_reply(&self) -> bool {
+                #expects_reply
+            }
+        }
+
+        impl From<#realtime_discr> for #discr_name {
+            fn from(command: #realtime_discr) -> Self {
+                match command {
+                    #(#same_arms)*
+                }
+            }
+        }
+
+        impl #enum_name {
+            /// Which real-time command this is, or `None` for commands that go through the
+            /// receive buffer.
+            pub const fn as_realtime(&self) -> Option<#realtime_discr> {
+                match self {
+                    #(#discr_arms)*
+                    _ => None,
+                }
+            }
+
+            /// Whether the printer processes the command as soon as it arrives.
+            pub const fn is_realtime(&self) -> bool {
+                self.as_realtime().is_some()
+            }
+        }
+    }
+}
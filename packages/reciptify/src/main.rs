@@ -11,11 +11,17 @@ use futures_core::Stream;
 use futures_util::StreamExt;
 use serde::Deserialize;
 use thermal::{
-    commands::{Command, reader::Output},
+    commands::{Command, reader::Output, writer::encoder},
     emulator::Emulator,
     state::{
         delta::Delta,
-        effect::{feed::Feed, print::Write},
+        effect::{
+            barcode::{Barcode, Symbology},
+            feed::Feed,
+            print::{Span, Write},
+            rule::Rule,
+            symbol::QrCode,
+        },
     },
     types::{
         cut_mode::{CutMode, CuttingShape},
@@ -38,18 +44,7 @@ struct Response {
 }
 
 pub async fn send(data: &[Output]) {
-    let data = data
-        .iter()
-        .map(|v| match v {
-            Output::Raw(x) => vec![*x],
-            Output::Command(c) => {
-                let mut x = vec![];
-                c.write_to(&mut x).unwrap();
-                x
-            }
-        })
-        .flatten()
-        .collect::<Vec<u8>>();
+    let data = encoder::to_bytes(data).unwrap();
 
     reqwest::Client::new()
         .post("http://10.100.1.159:8000/escpos")
@@ -62,8 +57,6 @@ pub async fn send(data: &[Output]) {
         .unwrap();
 }
 
-const CHAR_WIDTH: usize = 42;
-
 fn format_duration(d: Duration) -> String {
     let total_seconds = d.num_seconds();
     let sign = if total_seconds < 0 { "-" } else { "" };
@@ -134,8 +127,7 @@ async fn main() {
                     now.year()
                 )))
                 .with(Feed::lines(1))
-                .with(Write::from_str(&"-".repeat(CHAR_WIDTH)))
-                .with(Feed::lines(1))
+                .with(Rule::new())
                 .with(
                     Row::new()
                         .with_cell(4, "QTY".to_string(), pad::Alignment::Left)
@@ -144,9 +136,10 @@ async fn main() {
                         .with_cell(5, "AMT".to_string(), pad::Alignment::Right),
                 )
                 .with(Feed::lines(1))
-                .with(Write::from_str(&"-".repeat(CHAR_WIDTH)))
+                .with(Rule::new())
                 .with(res.toptracks)
-                .with(Write::from_str(&"-".repeat(CHAR_WIDTH)))
+                .with(Feed::lines(1))
+                .with(Rule::new())
                 .with(
                     Row::new()
                         .with_cell(21, "ITEM COUNT:".to_string(), pad::Alignment::Left)
@@ -165,10 +158,17 @@ async fn main() {
                 .with(Feed::lines(1))
                 .with(Write::from_str("AUTH CODE: 123421"))
                 .with(Feed::lines(1))
-                .with(Write::from_str(&format!(
-                    "CARDHOLDER: {}",
-                    args.username.to_uppercase()
-                ))),
+                .with(Write::rich([
+                    Span::new("CARDHOLDER: "),
+                    Span::new(&args.username.to_uppercase()).with_emphasized(true),
+                ]))
+                .with(Feed::lines(2))
+                .with(Barcode::new(Symbology::Code128, "0001").with_height(80))
+                .with(Feed::lines(1))
+                .with(
+                    QrCode::new(format!("https://www.last.fm/user/{}", args.username))
+                        .with_module_size(6),
+                ),
         )
         .unwrap();
 
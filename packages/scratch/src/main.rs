@@ -10,6 +10,7 @@ use thermal::{
     commands::{
         Command,
         reader::{Output, state::ParserState},
+        writer::encoder,
     },
     emulator::Emulator,
     state::{delta::Delta, effect::print::Write},
@@ -27,18 +28,7 @@ use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use winnow::Partial;
 
 pub fn send(data: &[Output]) {
-    let data = data
-        .iter()
-        .map(|v| match v {
-            Output::Raw(x) => vec![*x],
-            Output::Command(c) => {
-                let mut x = vec![];
-                c.write_to(&mut x).unwrap();
-                x
-            }
-        })
-        .flatten()
-        .collect::<Vec<u8>>();
+    let data = encoder::to_bytes(data).unwrap();
 
     Client::new()
         .post("http://10.100.1.159:8000/escpos")
@@ -0,0 +1,204 @@
+//! `diff`: what changed between two generated specs, for reviewing a regenerated spec.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::{fs, path::Path};
+
+pub fn cmd_diff(old: &Path, new: &Path, schema: &Path) -> Result<()> {
+    let old_spec = read_json(old)?;
+    let new_spec = read_json(new)?;
+
+    // Report the changes even if the new spec is invalid; they usually explain why
+    let report = Report::new(&old_spec, &new_spec);
+    report.print();
+
+    let schema = read_json(schema)?;
+    let validator =
+        jsonschema::validator_for(&schema).map_err(|e| anyhow!("invalid schema: {e}"))?;
+    let errors: Vec<String> = validator
+        .iter_errors(&new_spec)
+        .map(|e| format!("{}: {e}", e.instance_path))
+        .collect();
+    if !errors.is_empty() {
+        for e in &errors {
+            eprintln!("schema: {e}");
+        }
+        return Err(anyhow!(
+            "{} doesn't match the schema ({} error(s))",
+            new.display(),
+            errors.len()
+        ));
+    }
+
+    eprintln!("{} matches the schema", new.display());
+    Ok(())
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let txt = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_str(&txt).with_context(|| format!("parse json {}", path.display()))
+}
+
+/// A command as far as the diff is concerned.
+struct Entry<'a> {
+    id: &'a str,
+    name: &'a str,
+    /// What the variant is named after: `to_string` if set, else the name.
+    variant: &'a str,
+    category: &'a str,
+    content: Option<&'a Value>,
+}
+
+impl<'a> Entry<'a> {
+    fn from_value(v: &'a Value) -> Option<Self> {
+        let name = v["name"].as_str()?;
+        Some(Entry {
+            id: v["id"].as_str()?,
+            name,
+            variant: v["to_string"].as_str().unwrap_or(name),
+            category: v["category"].as_str().unwrap_or_default(),
+            content: v.get("content"),
+        })
+    }
+}
+
+fn entries(spec: &Value) -> Vec<Entry<'_>> {
+    spec["commands"]
+        .as_array()
+        .map(|cmds| cmds.iter().filter_map(Entry::from_value).collect())
+        .unwrap_or_default()
+}
+
+fn strings(v: &Value) -> Vec<&str> {
+    v.as_array()
+        .map(|a| a.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn content_str(content: Option<&Value>) -> String {
+    match content {
+        Some(c) => format!("[{}]", strings(c).join(", ")),
+        None => "none".to_string(),
+    }
+}
+
+struct Report {
+    categories: Vec<String>,
+    commands: Vec<String>,
+    added: usize,
+    removed: usize,
+    changed: usize,
+}
+
+impl Report {
+    fn new(old: &Value, new: &Value) -> Self {
+        let mut report = Report {
+            categories: Vec::new(),
+            commands: Vec::new(),
+            added: 0,
+            removed: 0,
+            changed: 0,
+        };
+
+        let (old_cats, new_cats) = (strings(&old["categories"]), strings(&new["categories"]));
+        for c in old_cats.iter().filter(|c| !new_cats.contains(c)) {
+            report.categories.push(format!("- {c}"));
+        }
+        for c in new_cats.iter().filter(|c| !old_cats.contains(c)) {
+            report.categories.push(format!("+ {c}"));
+        }
+
+        let (old_cmds, new_cmds) = (entries(old), entries(new));
+        let mut removed: Vec<&Entry> = old_cmds
+            .iter()
+            .filter(|o| !new_cmds.iter().any(|n| n.id == o.id))
+            .collect();
+        let mut added: Vec<&Entry> = new_cmds
+            .iter()
+            .filter(|n| !old_cmds.iter().any(|o| o.id == n.id))
+            .collect();
+
+        // A page that moved keeps its name, so pair those up instead of listing them twice
+        removed.retain(|o| {
+            let Some(i) = added
+                .iter()
+                .position(|n| n.name == o.name && n.category == o.category)
+            else {
+                return true;
+            };
+            let n = added.remove(i);
+            report
+                .commands
+                .push(format!("~ {}: id changed to {}", o.id, n.id));
+            report.changed += 1;
+            false
+        });
+
+        for o in &removed {
+            report.commands.push(format!("- {} ({})", o.id, o.name));
+        }
+        for n in &added {
+            report.commands.push(format!(
+                "+ {} ({}) in {}, content {}",
+                n.id,
+                n.name,
+                n.category,
+                content_str(n.content)
+            ));
+        }
+        report.removed = removed.len();
+        report.added = added.len();
+
+        for n in &new_cmds {
+            let Some(o) = old_cmds.iter().find(|o| o.id == n.id) else {
+                continue;
+            };
+
+            let mut changes = Vec::new();
+            if o.name != n.name {
+                changes.push(format!("renamed {:?} -> {:?}", o.name, n.name));
+            }
+            if o.variant != n.variant {
+                changes.push(format!("variant {:?} -> {:?}", o.variant, n.variant));
+            }
+            if o.category != n.category {
+                changes.push(format!("category {:?} -> {:?}", o.category, n.category));
+            }
+            if o.content != n.content {
+                changes.push(format!(
+                    "content {} -> {}",
+                    content_str(o.content),
+                    content_str(n.content)
+                ));
+            }
+
+            if !changes.is_empty() {
+                report.changed += 1;
+                report
+                    .commands
+                    .extend(changes.into_iter().map(|c| format!("~ {}: {c}", n.id)));
+            }
+        }
+
+        report
+    }
+
+    fn print(&self) {
+        if !self.categories.is_empty() {
+            println!("categories:");
+            for line in &self.categories {
+                println!("  {line}");
+            }
+        }
+        if !self.commands.is_empty() {
+            println!("commands:");
+            for line in &self.commands {
+                println!("  {line}");
+            }
+        }
+        println!(
+            "{} added, {} removed, {} changed",
+            self.added, self.removed, self.changed
+        );
+    }
+}
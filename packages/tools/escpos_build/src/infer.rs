@@ -0,0 +1,281 @@
+//! `infer`: propose `content` signatures from the pages' "[Format]" sections.
+//!
+//! The proposals go to a draft overrides file in the `--labels` format, so they can be
+//! reviewed and then merged into vendor/content.json by hand.
+
+use anyhow::Result;
+use scraper::{Html, Selector};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{extract_codes, load_overrides, parse_index_links, Pages, Parameter};
+
+/// A proposed override for one command.
+#[derive(Debug, Serialize)]
+struct Proposal {
+    /// Single-byte parameters mapped to their field, so `validate()` checks their range.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parameters: Vec<Parameter>,
+    content: Vec<String>,
+    /// Only when every parameter is one the macro can declare (bytes, `xL xH` pairs and
+    /// `pL pH` blocks); data runs need a hand-written parser.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<Encoding>,
+    /// The format lines the proposal comes from, for the reviewer. Ignored by `build`.
+    #[serde(rename = "_format")]
+    format: Vec<String>,
+    /// Set when the page lists formats with different parameters.
+    #[serde(rename = "_ambiguous", skip_serializing_if = "std::ops::Not::not")]
+    ambiguous: bool,
+}
+
+/// The `encoding` override, in the order the vendored files use.
+#[derive(Debug, Clone, Serialize)]
+struct Encoding {
+    prefix: Vec<u8>,
+    params: Vec<Value>,
+}
+
+/// Proposals in index order.
+struct Draft(Vec<(String, Proposal)>);
+
+impl Serialize for Draft {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (id, proposal) in &self.0 {
+            map.serialize_entry(id, proposal)?;
+        }
+        map.end()
+    }
+}
+
+/// One parameter of a format line.
+#[derive(Debug)]
+enum Token {
+    /// A byte, e.g. `n`.
+    Byte(String),
+    /// A little-endian pair, e.g. `nL nH`.
+    Word,
+    /// A constant byte after the first parameter, e.g. the `00` ending `ESC D`.
+    Fixed(u8),
+    /// `pL pH`: the number of bytes that follow.
+    Length,
+    /// A run of bytes, e.g. `d1...dk`, or anything else the encoding can't declare.
+    Data,
+}
+
+fn is_hex_byte(tok: &str) -> bool {
+    tok.len() == 2
+        && tok
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c))
+}
+
+/// Splits a hex format line like `1D 28 6B pL pH 31 41 n1 n2` into its prefix bytes and
+/// parameters.
+fn tokenize(line: &str) -> (Vec<u8>, Vec<Token>) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let prefix: Vec<u8> = words
+        .iter()
+        .map_while(|w| {
+            is_hex_byte(w)
+                .then(|| u8::from_str_radix(w, 16).ok())
+                .flatten()
+        })
+        .collect();
+
+    let mut tokens = Vec::new();
+    let mut rest = words[prefix.len()..].iter().peekable();
+    while let Some(w) = rest.next() {
+        if w.contains("...") || w.contains('…') || w.contains('[') || w.contains(']') {
+            // In `n1 ... nk` the run starts with the byte before the ellipsis
+            if matches!(*w, "..." | "…") && matches!(tokens.last(), Some(Token::Byte(_))) {
+                tokens.pop();
+            }
+            tokens.push(Token::Data);
+            break;
+        }
+        if is_hex_byte(w) {
+            tokens.push(Token::Fixed(u8::from_str_radix(w, 16).unwrap()));
+            continue;
+        }
+
+        if let Some(stem) = w.strip_suffix('L') {
+            if rest
+                .peek()
+                .is_some_and(|h| h.strip_suffix('H') == Some(stem))
+            {
+                rest.next();
+                tokens.push(if stem == "p" {
+                    Token::Length
+                } else {
+                    Token::Word
+                });
+                continue;
+            }
+        }
+
+        tokens.push(Token::Byte(w.to_string()));
+    }
+
+    (prefix, tokens)
+}
+
+/// Content types, parameter mappings and, if possible, the encoding for one format line.
+fn propose(line: &str) -> Option<(Vec<String>, Vec<Parameter>, Option<Encoding>)> {
+    let (prefix, tokens) = tokenize(line);
+    if prefix.is_empty() || tokens.is_empty() {
+        return None;
+    }
+
+    let mut content = Vec::new();
+    let mut parameters = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+    let mut declarable = true;
+    // Index into `params` where the current `pL pH` block starts
+    let mut block_start = None;
+
+    for token in tokens {
+        match token {
+            Token::Byte(name) => {
+                parameters.push(Parameter {
+                    name,
+                    field: Some(content.len()),
+                    ..Default::default()
+                });
+                content.push("u8".to_string());
+                params.push(json!("u8"));
+            }
+            Token::Word => {
+                content.push("u16".to_string());
+                params.push(json!("le_u16"));
+            }
+            Token::Fixed(b) => match params.last_mut().and_then(|p| p.get_mut("fixed")) {
+                Some(Value::Array(fixed)) => fixed.push(json!(b)),
+                _ => params.push(json!({ "fixed": [b] })),
+            },
+            Token::Length => {
+                // Everything after the length is inside it
+                if block_start.is_some() {
+                    declarable = false;
+                }
+                block_start = Some(params.len());
+            }
+            Token::Data => {
+                content.push("Vec<u8>".to_string());
+                declarable = false;
+            }
+        }
+    }
+
+    if let Some(start) = block_start {
+        let block: Vec<Value> = params.drain(start..).collect();
+        params.push(json!({ "block": block }));
+    }
+
+    let encoding = declarable.then_some(Encoding { prefix, params });
+    Some((content, parameters, encoding))
+}
+
+/// Rewrites the encoding of a function page relative to its parent's: what follows the shared
+/// framing (`pL pH` included, if the parent reads it) is the function code and its parameters.
+fn function_encoding(enc: Encoding, parent: &Value) -> Option<Encoding> {
+    let framing: Vec<u8> = serde_json::from_value(parent.get("prefix")?.clone()).ok()?;
+    let mut prefix = enc.prefix.strip_prefix(&framing[..])?.to_vec();
+
+    let in_block = parent["params"][0].get("block").is_some();
+    let mut params = match (in_block, &enc.params[..]) {
+        (true, [block]) if block.get("block").is_some() => block["block"].as_array()?.clone(),
+        // Pages of functions with a fixed size write `pL pH` out in hex
+        (true, _) if prefix.len() > 2 => {
+            prefix.drain(..2);
+            enc.params
+        }
+        (true, _) => return None,
+        (false, _) => enc.params,
+    };
+
+    // Constant bytes right after the framing are part of the function code
+    while let Some(fixed) = params.first().and_then(|p| p.get("fixed")) {
+        prefix.extend(serde_json::from_value::<Vec<u8>>(fixed.clone()).ok()?);
+        params.remove(0);
+    }
+
+    (!prefix.is_empty()).then_some(Encoding { prefix, params })
+}
+
+pub fn cmd_infer(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Result<()> {
+    let overrides = load_overrides(&labels)?;
+    let pages = Pages::open(src_dir)?;
+    let entries = parse_index_links(&Html::parse_document(&pages.read("commands.html")?))?;
+    let h_sel = Selector::parse("h2, h3").unwrap();
+
+    let mut draft = Vec::new();
+    let mut skipped = 0;
+    for ent in &entries {
+        // Commands that already have content are reviewed already, and the content of
+        // commands with functions is implied
+        if overrides
+            .per_id
+            .get(&ent.id)
+            .is_some_and(|o| o.content.is_some() || o.functions.is_some())
+        {
+            skipped += 1;
+            continue;
+        }
+
+        let html = pages.read(&format!("pages/{}", ent.filename))?;
+        let codes = extract_codes(&Html::parse_document(&html), &h_sel);
+
+        let proposals: Vec<_> = codes.hex.iter().filter_map(|l| propose(l)).collect();
+        let Some((content, parameters, encoding)) = proposals.first().cloned() else {
+            continue;
+        };
+        let ambiguous = proposals.iter().any(|(c, _, _)| c != &content);
+
+        // Functions are parsed after their parent's framing
+        let parent = overrides
+            .per_id
+            .iter()
+            .find(|(id, o)| o.functions.is_some() && ent.id.starts_with(&format!("{id}_fn")));
+        let encoding = match parent {
+            Some((_, o)) => encoding
+                .zip(o.encoding.as_ref())
+                .and_then(|(e, parent)| function_encoding(e, parent)),
+            None => encoding,
+        };
+
+        draft.push((
+            ent.id.clone(),
+            Proposal {
+                parameters,
+                content,
+                encoding: encoding.filter(|_| !ambiguous),
+                format: codes.hex,
+                ambiguous,
+            },
+        ));
+    }
+
+    eprintln!(
+        "proposed content for {} ids ({} already have content or functions)",
+        draft.len(),
+        skipped
+    );
+
+    let mut json = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    Draft(draft).serialize(&mut serde_json::Serializer::with_formatter(
+        &mut json, formatter,
+    ))?;
+
+    let out_path = out.unwrap_or_else(|| PathBuf::from("vendor").join("content.draft.json"));
+    fs::write(&out_path, json)?;
+    eprintln!("wrote {}", out_path.display());
+    Ok(())
+}
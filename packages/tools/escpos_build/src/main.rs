@@ -1,12 +1,18 @@
+mod diff;
+mod infer;
+
 use anyhow::{anyhow, Context, Result};
 use clap::{Parser, Subcommand};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use regex::Regex;
 use reqwest::blocking::Client;
 use scraper::ElementRef;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::{
     fs,
+    io::Read,
     path::{Path, PathBuf},
 };
 use url::Url;
@@ -32,10 +38,14 @@ enum Cmd {
     Fetch {
         /// Output folder (will be created)
         out_dir: PathBuf,
+
+        /// Also pack the folder into this `.tar.gz`, for `build` to read later
+        #[arg(long = "archive")]
+        archive: Option<PathBuf>,
     },
-    /// Parse a local folder (from `fetch`) and build spec JSON
+    /// Parse a local folder or archive (from `fetch`) and build spec JSON
     Build {
-        /// Folder that contains the downloaded HTML files
+        /// Folder that contains the downloaded HTML files, or a `.tar.gz` of it
         src_dir: PathBuf,
         /// Output JSON path (default: ./spec/commands.json)
         #[arg(short = 'o', long = "out")]
@@ -45,11 +55,38 @@ enum Cmd {
         #[arg(short = 'L', long = "labels")]
         labels: Vec<PathBuf>,
     },
+    /// Propose `content` for commands that have none, from the pages' [Format] sections
+    Infer {
+        /// Folder that contains the downloaded HTML files, or a `.tar.gz` of it
+        src_dir: PathBuf,
+        /// Output JSON path (default: ./vendor/content.draft.json)
+        #[arg(short = 'o', long = "out")]
+        out: Option<PathBuf>,
+
+        /// Existing override files; commands they give content to are skipped
+        #[arg(short = 'L', long = "labels")]
+        labels: Vec<PathBuf>,
+    },
+    /// Compare two spec JSON files and check the new one against the schema
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+
+        /// JSON schema the new spec must match
+        #[arg(
+            long = "schema",
+            default_value = "vendor/spec/escpos-commands.schema.json"
+        )]
+        schema: PathBuf,
+    },
 }
 
 #[derive(Debug, Serialize)]
 struct Root {
+    base_url: String,
     categories: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    models: Vec<String>,
     commands: Vec<Command>,
 }
 
@@ -60,16 +97,161 @@ struct Command {
     category: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     to_string: Option<String>,
+    /// Name of the enum holding the command's functions, from the overrides.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    functions: Option<String>,
+    /// Id of the command this one is a function of, e.g. `gs_lparen_lk` for
+    /// `gs_lparen_lk_fn180`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    family: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     detailed_message: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    notes: Vec<String>,
+    notes: Vec<Note>,
     #[serde(default)]
     obsolete: bool,
+    #[serde(skip_serializing_if = "Codes::is_empty")]
+    codes: Codes,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parameters: Vec<Parameter>,
+    /// `None` when the page has no model table.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    models: Option<Vec<ModelSupport>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     content: Option<Vec<String>>,
+    /// Wire layout (prefix bytes + parameters), passed through to the macro as-is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<serde_json::Value>,
+}
+
+/// One entry of the "[Notes]" section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Note {
+    Text(String),
+    /// A table inside a note, e.g. the bits of a status byte.
+    Table {
+        table: Table,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Table {
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// The "[Format]" section of a command page, one entry per format line.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Codes {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ascii: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hex: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dec: Vec<String>,
+}
+
+impl Codes {
+    fn is_empty(&self) -> bool {
+        self.ascii.is_empty() && self.hex.is_empty() && self.dec.is_empty()
+    }
+
+    /// The bytes of each hex line before its first parameter.
+    fn hex_prefixes(&self) -> Vec<Vec<u8>> {
+        self.hex
+            .iter()
+            .map(|line| {
+                line.split_whitespace()
+                    .map_while(|tok| u8::from_str_radix(tok, 16).ok().filter(|_| tok.len() == 2))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// One parameter of the "[Range]" and "[Default]" sections.
+///
+/// `field` (and `accessor`, for fields of a content type) tie the parameter to the variant's
+/// content so the macro can check it, and `names` give the bytes of a content type's variants;
+/// pages don't know about content, so those come from the override files.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Parameter {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    range: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min: Option<Bound>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max: Option<Bound>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    values: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    field: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accessor: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    names: BTreeMap<String, u8>,
+}
+
+/// A model listed on a command page, with the narrower ranges it accepts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ModelSupport {
+    model: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    parameters: Vec<Parameter>,
+}
+
+/// A range bound: a number, or the name of another parameter (`t1 ≤ t2`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Bound {
+    Value(i64),
+    Param(String),
+}
+
+impl Parameter {
+    /// Overlay whatever `other` sets on top of `self`.
+    fn merge(&mut self, other: &Parameter) {
+        if other.range.is_some() {
+            self.range = other.range.clone();
+        }
+        if other.default.is_some() {
+            self.default = other.default.clone();
+        }
+        if other.min.is_some() {
+            self.min = other.min.clone();
+        }
+        if other.max.is_some() {
+            self.max = other.max.clone();
+        }
+        if !other.values.is_empty() {
+            self.values = other.values.clone();
+        }
+        if other.field.is_some() {
+            self.field = other.field;
+        }
+        if other.accessor.is_some() {
+            self.accessor = other.accessor.clone();
+        }
+        if !other.names.is_empty() {
+            self.names = other.names.clone();
+        }
+    }
+}
+
+/// Merge parameters by name, appending the ones `base` doesn't have yet.
+fn merge_parameters(base: &mut Vec<Parameter>, add: &[Parameter]) {
+    for p in add {
+        match base.iter_mut().find(|b| b.name == p.name) {
+            Some(b) => b.merge(p),
+            None => base.push(p.clone()),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -82,13 +264,20 @@ struct IndexEntry {
 #[derive(Debug, Default, Clone, Deserialize)]
 struct CommandOverride {
     to_string: Option<String>,
+    functions: Option<String>,
     message: Option<String>,
     name: Option<String>,
     category: Option<String>,
     detailed_message: Option<String>,
-    notes: Option<Vec<String>>,
+    notes: Option<Vec<Note>>,
     obsolete: Option<bool>,
+    /// Replaces the "[Format]" codes; checked against the page's when it has them.
+    codes: Option<Codes>,
     content: Option<Vec<String>>,
+    encoding: Option<serde_json::Value>,
+    /// Merged into the extracted parameters by name.
+    parameters: Option<Vec<Parameter>>,
+    models: Option<Vec<ModelSupport>>,
 }
 
 #[derive(Debug, Default)]
@@ -100,12 +289,18 @@ struct LoadedOverrides {
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli.cmd {
-        Cmd::Fetch { out_dir } => cmd_fetch(&out_dir),
+        Cmd::Fetch { out_dir, archive } => cmd_fetch(&out_dir, archive.as_deref()),
         Cmd::Build {
             src_dir,
             out,
             labels,
         } => cmd_build(&src_dir, out, labels),
+        Cmd::Infer {
+            src_dir,
+            out,
+            labels,
+        } => infer::cmd_infer(&src_dir, out, labels),
+        Cmd::Diff { old, new, schema } => diff::cmd_diff(&old, &new, &schema),
     }
 }
 
@@ -154,6 +349,9 @@ fn merge_override_into(base: &mut CommandOverride, add: &CommandOverride) {
     if add.to_string.is_some() {
         base.to_string = add.to_string.clone();
     }
+    if add.functions.is_some() {
+        base.functions = add.functions.clone();
+    }
     if add.message.is_some() {
         base.message = add.message.clone();
     }
@@ -172,12 +370,24 @@ fn merge_override_into(base: &mut CommandOverride, add: &CommandOverride) {
     if add.obsolete.is_some() {
         base.obsolete = add.obsolete;
     }
+    if add.codes.is_some() {
+        base.codes = add.codes.clone();
+    }
     if add.content.is_some() {
         base.content = add.content.clone();
     }
+    if add.encoding.is_some() {
+        base.encoding = add.encoding.clone();
+    }
+    if let Some(add) = &add.parameters {
+        merge_parameters(base.parameters.get_or_insert_with(Vec::new), add);
+    }
+    if add.models.is_some() {
+        base.models = add.models.clone();
+    }
 }
 
-fn cmd_fetch(out_dir: &Path) -> Result<()> {
+fn cmd_fetch(out_dir: &Path, archive: Option<&Path>) -> Result<()> {
     fs::create_dir_all(out_dir).with_context(|| format!("create {}", out_dir.display()))?;
     let pages_dir = out_dir.join("pages");
     fs::create_dir_all(&pages_dir)?;
@@ -206,22 +416,96 @@ fn cmd_fetch(out_dir: &Path) -> Result<()> {
         out_dir.display(),
         out_dir.display()
     );
+
+    if let Some(archive) = archive {
+        let file =
+            fs::File::create(archive).with_context(|| format!("create {}", archive.display()))?;
+        let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::best()));
+        tar.append_path_with_name(out_dir.join("commands.html"), "commands.html")?;
+        tar.append_dir_all("pages", &pages_dir)?;
+        tar.into_inner()?.finish()?;
+        eprintln!("packed into {}", archive.display());
+    }
+
     Ok(())
 }
 
-fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Result<()> {
-    let overrides = load_overrides(&labels)?;
-    let index_path = src_dir.join("commands.html");
-    let pages_dir = src_dir.join("pages");
+/// Where `build` reads the index and the command pages from.
+enum Pages {
+    /// A folder written by `fetch`.
+    Dir(PathBuf),
+    /// A `.tar.gz` of such a folder, keyed by path relative to the folder.
+    Archive(HashMap<String, String>),
+}
+
+impl Pages {
+    fn open(src: &Path) -> Result<Self> {
+        if src.is_dir() {
+            if !src.join("commands.html").exists() {
+                return Err(anyhow!(
+                    "index not found at {}",
+                    src.join("commands.html").display()
+                ));
+            }
+            if !src.join("pages").is_dir() {
+                return Err(anyhow!(
+                    "pages folder not found at {}",
+                    src.join("pages").display()
+                ));
+            }
+            return Ok(Pages::Dir(src.to_path_buf()));
+        }
+
+        let file = fs::File::open(src).with_context(|| format!("open {}", src.display()))?;
+        let mut tar = tar::Archive::new(GzDecoder::new(file));
+        let mut files = HashMap::new();
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.to_string_lossy().replace('\\', "/");
+            let mut html = String::new();
+            entry
+                .read_to_string(&mut html)
+                .with_context(|| format!("read {path} in {}", src.display()))?;
+            files.insert(path.trim_start_matches("./").to_string(), html);
+        }
 
-    if !index_path.exists() {
-        return Err(anyhow!("index not found at {}", index_path.display()));
+        // Archives of the folder itself rather than its contents have one more level
+        let root = files
+            .keys()
+            .find_map(|k| k.strip_suffix("commands.html"))
+            .ok_or_else(|| anyhow!("index not found in {}", src.display()))?
+            .to_string();
+        let files = files
+            .into_iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix(&root)?.to_string(), v)))
+            .collect();
+
+        Ok(Pages::Archive(files))
     }
-    if !pages_dir.is_dir() {
-        return Err(anyhow!("pages folder not found at {}", pages_dir.display()));
+
+    /// Reads `path`, relative to the folder `fetch` wrote.
+    fn read(&self, path: &str) -> Result<String> {
+        match self {
+            Pages::Dir(dir) => {
+                let path = dir.join(path);
+                fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))
+            }
+            Pages::Archive(files) => files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow!("{path} not found in archive")),
+        }
     }
+}
+
+fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Result<()> {
+    let overrides = load_overrides(&labels)?;
+    let pages = Pages::open(src_dir)?;
 
-    let index_html = fs::read_to_string(&index_path)?;
+    let index_html = pages.read("commands.html")?;
     let index_doc = Html::parse_document(&index_html);
     let entries = parse_index_links(&index_doc)?;
 
@@ -236,25 +520,37 @@ fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Resu
     // Build commands by parsing each local page
     let mut commands = Vec::with_capacity(entries.len());
     for (i, ent) in entries.iter().enumerate() {
-        let path = pages_dir.join(&ent.filename);
-        eprintln!(
-            "[{}/{}] building from {}",
-            i + 1,
-            entries.len(),
-            path.display()
-        );
-        let html = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
-        let (name, detailed, notes, obsolete) = parse_command_page_minimal(&html)?;
+        let path = format!("pages/{}", ent.filename);
+        eprintln!("[{}/{}] building from {}", i + 1, entries.len(), path);
+        let html = pages.read(&path)?;
+        let PageDetails {
+            name,
+            detailed,
+            notes,
+            obsolete,
+            codes,
+            parameters,
+            models: page_models,
+        } = parse_command_page_minimal(&html)?;
+        if codes.is_empty() {
+            eprintln!("  warning: no [Format] section in {path}");
+        }
         commands.push(Command {
             id: ent.id.clone(), // <-- NEW
             name,
             category: ent.category.clone(),
             to_string: None,
+            functions: None,
+            family: None,
             message: None,
             detailed_message: detailed,
             notes,
             obsolete,
+            codes,
+            parameters,
+            models: page_models,
             content: None,
+            encoding: None,
         });
     }
 
@@ -273,6 +569,7 @@ fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Resu
 
     // Apply per-id overrides and track changes
     let mut applied = Vec::new();
+    let mut mismatched = Vec::new();
     for cmd in &mut commands {
         if let Some(ov) = overrides.per_id.get(&cmd.id) {
             let mut changed = false;
@@ -289,6 +586,12 @@ fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Resu
                     changed = true;
                 }
             }
+            if let Some(v) = &ov.functions {
+                if cmd.functions.as_ref() != Some(v) {
+                    cmd.functions = Some(v.clone());
+                    changed = true;
+                }
+            }
             if let Some(v) = &ov.message {
                 if cmd.message.as_ref() != Some(v) {
                     cmd.message = Some(v.clone());
@@ -313,12 +616,38 @@ fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Resu
                     changed = true;
                 }
             }
+            if let Some(v) = &ov.codes {
+                if !cmd.codes.is_empty() && cmd.codes.hex_prefixes() != v.hex_prefixes() {
+                    mismatched.push(cmd.id.clone());
+                }
+                if &cmd.codes != v {
+                    cmd.codes = v.clone();
+                    changed = true;
+                }
+            }
             if let Some(v) = &ov.content {
                 if cmd.content.as_ref() != Some(v) {
                     cmd.content = Some(v.clone());
                     changed = true;
                 }
             }
+            if let Some(v) = &ov.encoding {
+                if cmd.encoding.as_ref() != Some(v) {
+                    cmd.encoding = Some(v.clone());
+                    changed = true;
+                }
+            }
+            if let Some(v) = &ov.parameters {
+                let before = cmd.parameters.clone();
+                merge_parameters(&mut cmd.parameters, v);
+                changed |= cmd.parameters != before;
+            }
+            if let Some(v) = &ov.models {
+                if cmd.models.as_ref() != Some(v) {
+                    cmd.models = Some(v.clone());
+                    changed = true;
+                }
+            }
 
             if let Some(new_cat) = &ov.category {
                 if &cmd.category != new_cat {
@@ -337,6 +666,29 @@ fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Resu
         }
     }
 
+    // Codes overrides are typed in by hand, so one that disagrees with the page's parsed
+    // [Format] prefix is a mistake on one side or the other
+    if !mismatched.is_empty() {
+        return Err(anyhow!(
+            "codes overrides don't match the [Format] section of: {}",
+            mismatched.join(", ")
+        ));
+    }
+
+    // The function pages of a command that declares functions share its id, e.g.
+    // `gs_lparen_lk_fn180` for `gs_lparen_lk`
+    let parents: Vec<String> = commands
+        .iter()
+        .filter(|c| c.functions.is_some())
+        .map(|c| c.id.clone())
+        .collect();
+    for cmd in &mut commands {
+        cmd.family = parents
+            .iter()
+            .find(|p| cmd.id.starts_with(&format!("{p}_fn")))
+            .cloned();
+    }
+
     // Tiny report
     if !applied.is_empty() {
         eprintln!("Applied overrides for {} ids:", applied.len());
@@ -345,8 +697,18 @@ fn cmd_build(src_dir: &Path, out: Option<PathBuf>, labels: Vec<PathBuf>) -> Resu
         }
     }
 
+    // Models in first-seen order, like categories
+    let mut models: Vec<String> = Vec::new();
+    for support in commands.iter().flat_map(|c| c.models.iter().flatten()) {
+        if !models.contains(&support.model) {
+            models.push(support.model.clone());
+        }
+    }
+
     let root = Root {
+        base_url: BASE_URL.to_string(),
         categories,
+        models,
         commands,
     };
     let json = serde_json::to_string_pretty(&root)?;
@@ -442,7 +804,18 @@ fn find_ancestor_tag<'a>(
 
 // -------- parsing helpers (detail page) --------
 
-fn parse_command_page_minimal(html: &str) -> Result<(String, Option<String>, Vec<String>, bool)> {
+/// What a command's detail page contributes to its spec entry.
+struct PageDetails {
+    name: String,
+    detailed: Option<String>,
+    notes: Vec<Note>,
+    obsolete: bool,
+    codes: Codes,
+    parameters: Vec<Parameter>,
+    models: Option<Vec<ModelSupport>>,
+}
+
+fn parse_command_page_minimal(html: &str) -> Result<PageDetails> {
     let doc = Html::parse_document(html);
     let h_sel = Selector::parse("h2, h3").unwrap();
 
@@ -451,8 +824,324 @@ fn parse_command_page_minimal(html: &str) -> Result<(String, Option<String>, Vec
     let detailed = extract_block_after(&doc, &h_sel, "[Description]")?;
     let notes = extract_notes(&doc, &h_sel)?;
     let obsolete = detect_obsolete(&doc);
+    let codes = extract_codes(&doc, &h_sel);
+    let parameters = extract_parameters(&doc, &h_sel);
+    let models = extract_models(&doc, &h_sel);
 
-    Ok((name, detailed, notes, obsolete))
+    Ok(PageDetails {
+        name,
+        detailed,
+        notes,
+        obsolete,
+        codes,
+        parameters,
+        models,
+    })
+}
+
+/// Models from the page's model section (e.g. "[Model-dependent variations]").
+///
+/// A heading, list item or first table cell naming models starts their entry; the lines after
+/// it (or the rest of the row) are read like "[Range]" lines into restrictions for those
+/// models.
+fn extract_models(doc: &Html, h_sel: &Selector) -> Option<Vec<ModelSupport>> {
+    let model_re = Regex::new(r"\bTM-[A-Za-z0-9][A-Za-z0-9-]*").unwrap();
+    let cell_sel = Selector::parse("td, th").unwrap();
+    let line_sel = Selector::parse("li, p, tr, h4, h5").unwrap();
+    let ranges = RangeParser::new();
+
+    let h = doc.select(h_sel).find(|h| {
+        h.text()
+            .collect::<String>()
+            .to_ascii_lowercase()
+            .contains("model")
+    })?;
+    let level = h.value().name().to_ascii_lowercase();
+
+    let mut support: Vec<ModelSupport> = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+
+    let mut sib = h.next_sibling();
+    while let Some(n) = sib {
+        sib = n.next_sibling();
+        let Some(er) = ElementRef::wrap(n) else {
+            continue;
+        };
+
+        // Stop at the next heading of the same or a higher level.
+        let tag = er.value().name().to_ascii_lowercase();
+        if tag == "h2" || tag == level {
+            break;
+        }
+
+        let mut rows: Vec<Vec<String>> = er
+            .select(&line_sel)
+            .map(|l| {
+                if l.value().name().eq_ignore_ascii_case("tr") {
+                    cell_texts(&l, &cell_sel)
+                } else {
+                    vec![normalize_ws(&l.text().collect::<Vec<_>>().join(" "))]
+                }
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push(vec![normalize_ws(&er.text().collect::<Vec<_>>().join(" "))]);
+        }
+
+        for row in rows {
+            let Some((first, rest)) = row.split_first() else {
+                continue;
+            };
+
+            let named: Vec<&str> = model_re.find_iter(first).map(|m| m.as_str()).collect();
+            let lines = if named.is_empty() {
+                &row[..]
+            } else {
+                current = named
+                    .iter()
+                    .map(|name| {
+                        support
+                            .iter()
+                            .position(|s| s.model == *name)
+                            .unwrap_or_else(|| {
+                                support.push(ModelSupport {
+                                    model: name.to_string(),
+                                    parameters: Vec::new(),
+                                });
+                                support.len() - 1
+                            })
+                    })
+                    .collect();
+                rest
+            };
+
+            for line in lines {
+                for &i in &current {
+                    ranges.apply(&mut support[i].parameters, line);
+                }
+            }
+        }
+    }
+
+    (!support.is_empty()).then_some(support)
+}
+
+/// Text lines of the section under the first heading containing `marker`, up to the next
+/// h2/h3. List items, paragraphs and table rows each give one line.
+fn section_lines(doc: &Html, h_sel: &Selector, marker: &str) -> Vec<String> {
+    let line_sel = Selector::parse("li, p, tr").unwrap();
+    let mut lines = Vec::new();
+
+    let Some(h) = doc
+        .select(h_sel)
+        .find(|h| h.text().collect::<String>().contains(marker))
+    else {
+        return lines;
+    };
+
+    let mut sib = h.next_sibling();
+    while let Some(n) = sib {
+        if let Some(er) = ElementRef::wrap(n) {
+            let tag = er.value().name().to_ascii_lowercase();
+            if tag == "h2" || tag == "h3" {
+                break;
+            }
+
+            let mut nested: Vec<String> = er
+                .select(&line_sel)
+                .map(|l| normalize_ws(&l.text().collect::<Vec<_>>().join(" ")))
+                .collect();
+            if nested.is_empty() {
+                nested.push(normalize_ws(&er.text().collect::<Vec<_>>().join(" ")));
+            }
+            lines.extend(nested.into_iter().filter(|l| !l.is_empty()));
+        }
+        sib = n.next_sibling();
+    }
+
+    lines
+}
+
+/// Parameters from the "[Range]" and "[Default]" sections.
+///
+/// Understands the forms the reference uses: `0 ≤ n ≤ 255`, `n = 0, 1, 48, 49`, `n = 0 – 3`
+/// and `t1 ≤ t2`, several of which may share a line separated by commas or "and". The raw
+/// text is kept in `range` / `default` either way.
+/// Recognises the range notations of the reference; see [`extract_parameters`].
+struct RangeParser {
+    between: Regex,
+    relation: Regex,
+    values: Regex,
+}
+
+impl RangeParser {
+    fn new() -> Self {
+        Self {
+            between: Regex::new(r"^\(?(-?\d+)\)?\s*[≤<]=?\s*(\w+)\s*[≤<]=?\s*\(?(-?\d+)\)?$")
+                .unwrap(),
+            relation: Regex::new(r"^([A-Za-z]\w*)\s*[≤<]=?\s*([A-Za-z]\w*)$").unwrap(),
+            values: Regex::new(r"^(\w+)\s*=\s*(-?\d+(?:\s*[,–-]\s*-?\d+)*)$").unwrap(),
+        }
+    }
+
+    /// Record whatever ranges `line` gives into `params`.
+    fn apply(&self, params: &mut Vec<Parameter>, line: &str) {
+        // Lists keep their commas, so try the whole line first.
+        if let Some(c) = self.values.captures(line) {
+            let p = param_mut(params, &c[1]);
+            p.values = expand_values(&c[2]);
+            p.range = Some(line.to_string());
+            return;
+        }
+
+        for part in line.split([',', ';']).flat_map(|p| p.split(" and ")) {
+            let part = part.trim();
+            if let Some(c) = self.between.captures(part) {
+                let p = param_mut(params, &c[2]);
+                p.min = c[1].parse().ok().map(Bound::Value);
+                p.max = c[3].parse().ok().map(Bound::Value);
+                p.range = Some(part.to_string());
+            } else if let Some(c) = self.relation.captures(part) {
+                // `t1 ≤ t2`: keep it as the lower bound of the second parameter.
+                param_mut(params, &c[2]).min = Some(Bound::Param(c[1].to_string()));
+            }
+        }
+    }
+}
+
+fn extract_parameters(doc: &Html, h_sel: &Selector) -> Vec<Parameter> {
+    let ranges = RangeParser::new();
+    let mut params: Vec<Parameter> = Vec::new();
+
+    for line in section_lines(doc, h_sel, "[Range]") {
+        ranges.apply(&mut params, &line);
+    }
+
+    for line in section_lines(doc, h_sel, "[Default]") {
+        for part in line.split([',', ';']) {
+            let Some((name, value)) = part.split_once('=') else {
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            if !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric())
+                && !value.is_empty()
+            {
+                param_mut(&mut params, name).default = Some(value.to_string());
+            }
+        }
+    }
+
+    params
+}
+
+fn param_mut<'p>(params: &'p mut Vec<Parameter>, name: &str) -> &'p mut Parameter {
+    let i = match params.iter().position(|p| p.name == name) {
+        Some(i) => i,
+        None => {
+            params.push(Parameter {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            params.len() - 1
+        }
+    };
+    &mut params[i]
+}
+
+/// `0, 1, 48 – 51` -> `[0, 1, 48, 49, 50, 51]`.
+fn expand_values(list: &str) -> Vec<i64> {
+    let mut values = Vec::new();
+    for item in list.split(',') {
+        let bounds: Vec<i64> = item
+            .split(['–', '-'])
+            .filter_map(|v| v.trim().parse().ok())
+            .collect();
+        match bounds[..] {
+            [v] => values.push(v),
+            [lo, hi] if lo <= hi && hi - lo <= 256 => values.extend(lo..=hi),
+            _ => {}
+        }
+    }
+    values
+}
+
+/// Collects the ASCII / Hex / Decimal lines of the "[Format]" section.
+///
+/// The section is a table whose rows start with the notation, followed by one cell per token
+/// (`ASCII | ESC | J | n`). Commands with several forms (e.g. `GS V` function A and B) have one
+/// row per form and notation.
+fn extract_codes(doc: &Html, h_sel: &Selector) -> Codes {
+    let tr_sel = Selector::parse("tr").unwrap();
+    let cell_sel = Selector::parse("td, th").unwrap();
+
+    let mut codes = Codes::default();
+
+    for h in doc.select(h_sel) {
+        let t = h.text().collect::<Vec<_>>().join(" ");
+        if !t.contains("[Format]") {
+            continue;
+        }
+
+        // Rows of the format table(s) between this heading and the next one.
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut sib = h.next_sibling();
+        while let Some(n) = sib {
+            if let Some(er) = ElementRef::wrap(n) {
+                let tag = er.value().name().to_ascii_lowercase();
+                if tag == "h2" || tag == "h3" {
+                    break;
+                }
+
+                if tag == "tr" {
+                    rows.push(cell_texts(&er, &cell_sel));
+                } else {
+                    for tr in er.select(&tr_sel) {
+                        rows.push(cell_texts(&tr, &cell_sel));
+                    }
+                }
+
+                // Some pages put the format on plain lines instead of a table.
+                if rows.is_empty() {
+                    let txt = normalize_ws(&er.text().collect::<Vec<_>>().join(" "));
+                    for notation in ["ASCII", "Hex", "Decimal"] {
+                        if let Some(rest) = txt.strip_prefix(notation) {
+                            rows.push(vec![notation.to_string(), rest.trim().to_string()]);
+                        }
+                    }
+                }
+            }
+            sib = n.next_sibling();
+        }
+
+        for row in rows {
+            let Some((notation, tokens)) = row.split_first() else {
+                continue;
+            };
+            let line = normalize_ws(&tokens.join(" "));
+            if line.is_empty() {
+                continue;
+            }
+
+            match notation.to_ascii_lowercase().as_str() {
+                "ascii" => codes.ascii.push(line),
+                "hex" => codes.hex.push(line),
+                "decimal" | "dec" => codes.dec.push(line),
+                _ => {}
+            }
+        }
+
+        break;
+    }
+
+    codes
+}
+
+fn cell_texts(row: &ElementRef<'_>, cell_sel: &Selector) -> Vec<String> {
+    row.select(cell_sel)
+        .map(|td| normalize_ws(&td.text().collect::<Vec<_>>().join(" ")))
+        .filter(|s| !s.is_empty())
+        .collect()
 }
 
 // Finds the closest ancestor whose class list contains `class_name`.
@@ -475,8 +1164,13 @@ fn element_has_class(el: &ElementRef<'_>, cls: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn extract_notes(doc: &Html, h_sel: &Selector) -> Result<Vec<String>> {
+/// The items of the "[Notes]" list, as notes.
+///
+/// Some pages give the same paragraph twice in a row, e.g. the paper sensor note of `DLE EOT`,
+/// so a note repeating the one before it is dropped.
+fn extract_notes(doc: &Html, h_sel: &Selector) -> Result<Vec<Note>> {
     let ul_li_sel = Selector::parse("ul li").unwrap();
+    let tables = FlatTables::new();
 
     // Find the [Notes] heading
     for h in doc.select(h_sel) {
@@ -489,11 +1183,9 @@ fn extract_notes(doc: &Html, h_sel: &Selector) -> Result<Vec<String>> {
         if let Some(section) = find_ancestor_with_class(&h, "Header2") {
             let mut out = Vec::new();
             for li in section.select(&ul_li_sel) {
-                let txt = normalize_ws(&li.text().collect::<Vec<_>>().join(" "));
-                if !txt.is_empty() {
-                    out.push(txt);
-                }
+                out.extend(item_notes(&li, &tables));
             }
+            out.dedup();
             if !out.is_empty() {
                 return Ok(out);
             }
@@ -514,11 +1206,9 @@ fn extract_notes(doc: &Html, h_sel: &Selector) -> Result<Vec<String>> {
                     if let Some(ul) = er.select(&ul_sel).next() {
                         let mut out = Vec::new();
                         for li in ul.select(&li_sel) {
-                            let txt = normalize_ws(&li.text().collect::<Vec<_>>().join(" "));
-                            if !txt.is_empty() {
-                                out.push(txt);
-                            }
+                            out.extend(item_notes(&li, &tables));
                         }
+                        out.dedup();
                         if !out.is_empty() {
                             return Ok(out);
                         }
@@ -532,6 +1222,246 @@ fn extract_notes(doc: &Html, h_sel: &Selector) -> Result<Vec<String>> {
     Ok(Vec::new())
 }
 
+/// The notes in one list item: its text, with every table in it as a note of its own.
+fn item_notes(li: &ElementRef<'_>, tables: &FlatTables) -> Vec<Note> {
+    fn walk(el: &ElementRef<'_>, tables: &FlatTables, text: &mut String, out: &mut Vec<Note>) {
+        for child in el.children() {
+            if let Some(t) = child.value().as_text() {
+                text.push_str(&t.text);
+                text.push(' ');
+            } else if let Some(child) = ElementRef::wrap(child) {
+                if child.value().name().eq_ignore_ascii_case("table") {
+                    flush(tables, text, out);
+                    out.extend(parse_table(&child).map(|table| Note::Table { table }));
+                } else {
+                    walk(&child, tables, text, out);
+                }
+            }
+        }
+    }
+
+    fn flush(tables: &FlatTables, text: &mut String, out: &mut Vec<Note>) {
+        out.extend(tables.split(&normalize_ws(text)));
+        text.clear();
+    }
+
+    let mut out = Vec::new();
+    let mut text = String::new();
+    walk(li, tables, &mut text, &mut out);
+    flush(tables, &mut text, &mut out);
+    out
+}
+
+/// A table with its first row as the header.
+///
+/// Cells spanning rows (typically the bit number shared by a bit's two states) only appear in
+/// their first row, so shorter rows are padded at the front.
+fn parse_table(table: &ElementRef<'_>) -> Option<Table> {
+    let row_sel = Selector::parse("tr").unwrap();
+    let cell_sel = Selector::parse("td, th").unwrap();
+
+    let mut rows = table.select(&row_sel).map(|tr| {
+        tr.select(&cell_sel)
+            .map(|td| normalize_ws(&td.text().collect::<Vec<_>>().join(" ")))
+            .collect::<Vec<_>>()
+    });
+
+    let columns = rows.next()?;
+    let rows = rows
+        .filter(|r| r.iter().any(|c| !c.is_empty()))
+        .map(|mut r| {
+            if r.len() < columns.len() {
+                r.splice(0..0, vec![String::new(); columns.len() - r.len()]);
+            }
+            r
+        })
+        .collect();
+
+    Some(Table { columns, rows })
+}
+
+/// Tables that reach a note as one run of text, header first, instead of as a `<table>`.
+///
+/// Three layouts are recognised by their header: the "Send data" tables of transmitted data
+/// (`Send data | Hex | Decimal | Number of bytes`), bit tables (`Bit` and four of `Function`,
+/// `Status`, `Binary`, `Hex` and `Decimal`), and the `Other information` table of the symbol
+/// size functions. Text around a table is kept as notes of its own.
+struct FlatTables {
+    send_data: Regex,
+    send_data_row: Regex,
+    bits: Regex,
+    condition: Regex,
+    condition_row: Regex,
+}
+
+impl FlatTables {
+    fn new() -> Self {
+        let hex = r"[0-9A-F]{2}h(?:(?:, or |, | or | – )[0-9A-F]{2}h)*";
+        let dec = r"\d+(?:(?:, or |, | or | – |,)\d+)*";
+
+        Self {
+            send_data: Regex::new(
+                r"^(?P<pre>.*?)(?P<first>Type of information \( n \)|Send data|Power-off notice|Clear response)(?: Send data)? Hex Decimal Number of bytes (?P<body>.+)$",
+            )
+            .unwrap(),
+            // A status byte with a table of its own has a reference to it instead of values
+            send_data_row: Regex::new(&format!(
+                r"^(?P<label>.+?) (?P<hex>{hex}|See the [^.]+ table below\.) (?:(?P<dec>{dec}) )?(?P<bytes>(?:\d+(?:(?: – | or )\d+)?|\( nL \+ nH × 256\)) bytes?)(?: |$)"
+            ))
+            .unwrap(),
+            bits: Regex::new(
+                r"^(?P<pre>.*?)Bit (?P<columns>(?:(?:Function|Status|Binary|Hex|Decimal) ){3}(?:Function|Status|Binary|Hex|Decimal)) (?P<body>.+)$",
+            )
+            .unwrap(),
+            condition: Regex::new(
+                r"^(?P<pre>.*?)Other information Condition Hex Decimal (?P<body>.+)$",
+            )
+            .unwrap(),
+            condition_row: Regex::new(r"(?:^| )([0-9A-F]{2}h) (\d+) ").unwrap(),
+        }
+    }
+
+    /// `text` as notes, with the tables in it split into rows.
+    fn split(&self, text: &str) -> Vec<Note> {
+        let mut out = Vec::new();
+        if !text.is_empty() {
+            let table = self
+                .send_data_table(text)
+                .or_else(|| self.bit_table(text))
+                .or_else(|| self.condition_table(text));
+            match table {
+                Some((pre, table, rest)) => {
+                    out.extend(self.split(pre.trim()));
+                    out.push(Note::Table { table });
+                    out.extend(self.split(rest.trim()));
+                }
+                None => out.push(Note::Text(text.to_string())),
+            }
+        }
+        out
+    }
+
+    fn send_data_table<'t>(&self, text: &'t str) -> Option<(&'t str, Table, &'t str)> {
+        let c = self.send_data.captures(text)?;
+        let first = c.name("first").unwrap().as_str();
+        let columns = [first, "Hex", "Decimal", "Number of bytes"].map(str::to_string);
+
+        let (rows, rest) = rows(&self.send_data_row, c.name("body").unwrap().as_str(), |c| {
+            ["label", "hex", "dec", "bytes"]
+                .map(|name| c.name(name).map_or("", |m| m.as_str()).to_string())
+                .to_vec()
+        });
+        if rows.is_empty() {
+            return None;
+        }
+
+        let pre = c.name("pre").unwrap().as_str();
+        Some((
+            pre,
+            Table {
+                columns: columns.to_vec(),
+                rows,
+            },
+            rest,
+        ))
+    }
+
+    fn bit_table<'t>(&self, text: &'t str) -> Option<(&'t str, Table, &'t str)> {
+        let c = self.bits.captures(text)?;
+        let columns: Vec<String> = std::iter::once("Bit")
+            .chain(c.name("columns").unwrap().as_str().split(' '))
+            .map(str::to_string)
+            .collect();
+        // A row only ends where its values do
+        if matches!(columns[4].as_str(), "Function" | "Status") {
+            return None;
+        }
+
+        // The bit number spans the rows of its states, so only the first of them has it
+        let cells: Vec<String> = columns[1..]
+            .iter()
+            .map(|column| match column.as_str() {
+                "Binary" => r"([01]+|−)".to_string(),
+                "Hex" => r"([0-9A-F]{2}|−)".to_string(),
+                "Decimal" => r"(\d+|−)".to_string(),
+                _ => r"(.+?)".to_string(),
+            })
+            .collect();
+        let row = Regex::new(&format!(
+            r"^(?:(\d+(?:(?:, | – )\d+)?) )?{}(?: |$)",
+            cells.join(" ")
+        ))
+        .unwrap();
+
+        let (rows, rest) = rows(&row, c.name("body").unwrap().as_str(), |c| {
+            (1..=columns.len())
+                .map(|i| c.get(i).map_or("", |m| m.as_str()).to_string())
+                .collect()
+        });
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some((
+            c.name("pre").unwrap().as_str(),
+            Table { columns, rows },
+            rest,
+        ))
+    }
+
+    /// The condition of each row runs up to the next row, so it's found by the values.
+    fn condition_table<'t>(&self, text: &'t str) -> Option<(&'t str, Table, &'t str)> {
+        let c = self.condition.captures(text)?;
+        let body = c.name("body").unwrap().as_str();
+
+        let starts: Vec<_> = self.condition_row.captures_iter(body).collect();
+        if starts.first()?.get(0).unwrap().start() != 0 {
+            return None;
+        }
+
+        // The last condition runs up to the footnote that follows the table, if there is one
+        let last = starts.last().unwrap().get(0).unwrap().end();
+        let table_end = body[last..].find(" (∗").map_or(body.len(), |at| last + at);
+
+        let rows = starts
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let end = starts
+                    .get(i + 1)
+                    .map_or(table_end, |next| next.get(0).unwrap().start());
+                vec![
+                    row[1].to_string(),
+                    row[2].to_string(),
+                    body[row.get(0).unwrap().end()..end].trim().to_string(),
+                ]
+            })
+            .collect();
+
+        let columns = ["Hex", "Decimal", "Condition"].map(str::to_string).to_vec();
+        let rest = &body[table_end..];
+        Some((
+            c.name("pre").unwrap().as_str(),
+            Table { columns, rows },
+            rest,
+        ))
+    }
+}
+
+/// Rows matched by `row` one after another from the start of `body`, and the rest of it.
+fn rows<'t>(
+    row: &Regex,
+    mut body: &'t str,
+    cells: impl Fn(&regex::Captures<'_>) -> Vec<String>,
+) -> (Vec<Vec<String>>, &'t str) {
+    let mut rows = Vec::new();
+    while let Some(c) = row.captures(body) {
+        rows.push(cells(&c));
+        body = &body[c.get(0).unwrap().end()..];
+    }
+    (rows, body)
+}
+
 fn extract_block_after(doc: &Html, h_sel: &Selector, marker: &str) -> Result<Option<String>> {
     for h in doc.select(h_sel) {
         let t = h.text().collect::<Vec<_>>().join(" ");